@@ -0,0 +1,94 @@
+// Konfiguracja keepera (zmienne środowiskowe)
+//
+// Zmienne:
+// - KEEPER_RPC_URL - adres RPC (domyślnie solana-test-validator: http://127.0.0.1:8899)
+// - KEEPER_KEYPAIR - ścieżka do keypair keepera (domyślnie ~/.config/solana/id.json)
+// - KEEPER_PRIORITY_FEE - priority fee w micro-lamportach za CU (domyślnie 10_000)
+// - KEEPER_COMPUTE_UNITS - limit CU na transakcję (domyślnie 400_000)
+// - KEEPER_BATCH_SIZE - liczba pozycji w jednej transakcji (domyślnie 8)
+// - KEEPER_MAX_RETRIES - liczba prób wysłania transakcji (domyślnie 3)
+// - KEEPER_POLL_INTERVAL_SECS - odstęp między skanami (domyślnie 30)
+// - KEEPER_JUPITER_API - adres API Jupitera (domyślnie https://quote-api.jup.ag/v6)
+// - KEEPER_ROUTE_SLIPPAGE_BPS - slippage przy wycenie trasy (domyślnie 50);
+//   podłogę on-chain wyznacza wyrocznia i ProgramConfig.oracle_max_slippage_bps
+// - KEEPER_ROUTE_SOURCE - źródło tras: jupiter (API Jupitera, domyślnie) lub
+//   local (program localnet-mock pod adresem Jupitera na solana-test-validator)
+// - KEEPER_LOCAL_ROUTE_RATE - kurs trasy local: jednostki bazowe tokenu wypłaty
+//   za 1 LST (1e9 jednostek); musi pokryć podłogę z wyroczni
+// - KEEPER_RUN_ONCE - pojedynczy skan i wyjście (true/false, domyślnie false)
+//
+// Aktywa (mint, konto puli, wyrocznia) pobierane są z rejestru LstAsset programu
+
+use std::{env, str::FromStr, time::Duration};
+
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+const DEFAULT_JUPITER_API_URL: &str = "https://quote-api.jup.ag/v6";
+
+/// Źródło tras swapu LST zysku -> token wypłaty
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RouteSource {
+    Jupiter,
+    Local,
+}
+
+impl FromStr for RouteSource {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "jupiter" => Ok(Self::Jupiter),
+            "local" => Ok(Self::Local),
+            _ => Err(()),
+        }
+    }
+}
+
+pub struct KeeperConfig {
+    pub rpc_url: String,
+    pub keypair_path: String,
+    pub priority_fee_micro_lamports: u64,
+    pub compute_units: u32,
+    pub batch_size: usize,
+    pub max_retries: u32,
+    pub poll_interval: Duration,
+    pub jupiter_api_url: String,
+    pub route_slippage_bps: u16,
+    pub route_source: RouteSource,
+    pub local_route_rate: u64,
+    pub run_once: bool,
+}
+
+impl KeeperConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+
+        let config = Self {
+            rpc_url: env::var("KEEPER_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.to_string()),
+            keypair_path: env::var("KEEPER_KEYPAIR")
+                .unwrap_or_else(|_| format!("{}/.config/solana/id.json", home)),
+            priority_fee_micro_lamports: optional("KEEPER_PRIORITY_FEE", 10_000)?,
+            compute_units: optional("KEEPER_COMPUTE_UNITS", 400_000)?,
+            batch_size: optional("KEEPER_BATCH_SIZE", 8)?,
+            max_retries: optional("KEEPER_MAX_RETRIES", 3)?,
+            poll_interval: Duration::from_secs(optional("KEEPER_POLL_INTERVAL_SECS", 30)?),
+            jupiter_api_url: env::var("KEEPER_JUPITER_API")
+                .unwrap_or_else(|_| DEFAULT_JUPITER_API_URL.to_string()),
+            route_slippage_bps: optional("KEEPER_ROUTE_SLIPPAGE_BPS", 50)?,
+            route_source: optional("KEEPER_ROUTE_SOURCE", RouteSource::Jupiter)?,
+            local_route_rate: optional("KEEPER_LOCAL_ROUTE_RATE", 0)?,
+            run_once: optional("KEEPER_RUN_ONCE", false)?,
+        };
+        if config.route_source == RouteSource::Local && config.local_route_rate == 0 {
+            return Err("KEEPER_LOCAL_ROUTE_RATE is required for local routes".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+fn optional<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match env::var(name) {
+        Ok(value) => value.parse().map_err(|_| format!("{} is invalid: {}", name, value)),
+        Err(_) => Ok(default),
+    }
+}
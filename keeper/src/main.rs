@@ -0,0 +1,203 @@
+// yieldflow-keeper - off-chain keeper dla auto-wypłat YieldFlow
+//
+// Pętla główna:
+// 1. Pobiera ProgramConfig i aktywa z rejestru LstAsset
+// 2. Dla każdego aktywa: stan skarbca, kurs z puli i konta UserStake
+//    (getProgramAccounts) gotowe do wypłaty
+// 3. Grupuje pozycje wg tokenu wypłaty (payout_mint), dzieli grupy na paczki
+//    (KEEPER_BATCH_SIZE), pobiera trasę Jupitera dla LST zysku paczki
+//    i wysyła batch_crank_claim
+// 4. Czeka KEEPER_POLL_INTERVAL_SECS i powtarza (KEEPER_RUN_ONCE - jeden skan)
+//
+// Uruchomienie lokalne (solana-test-validator):
+//   anchor test
+// Walidator startuje z programem localnet-mock pod adresem Jupitera
+// i kontami Pyth z tests/fixtures (Anchor.toml), a tests/keeper.ts zakłada
+// aktywo, pozycję i dywidendę, po czym uruchamia keepera z
+// KEEPER_ROUTE_SOURCE=local i KEEPER_RUN_ONCE=true
+//
+// Konfiguracja: patrz config.rs
+
+mod config;
+mod route;
+mod scan;
+mod submit;
+
+use std::{
+    collections::BTreeMap,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::AccountDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use yield_flow::state::{FeeTiers, PauseTarget, ProgramConfig};
+
+use crate::{
+    config::KeeperConfig,
+    scan::{ProgramSnapshot, RegisteredAsset},
+    submit::CrankAccounts,
+};
+
+fn main() {
+    let config = match KeeperConfig::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Invalid configuration: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let keeper = match read_keypair_file(&config.keypair_path) {
+        Ok(keeper) => keeper,
+        Err(err) => {
+            eprintln!("Cannot read keypair {}: {}", config.keypair_path, err);
+            std::process::exit(1);
+        }
+    };
+    let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+    let http = reqwest::blocking::Client::new();
+
+    println!("yieldflow-keeper {} on {}", keeper.pubkey(), config.rpc_url);
+    loop {
+        let result = run_once(&rpc, &http, &keeper, &config);
+        if let Err(err) = &result {
+            eprintln!("Scan failed: {}", err);
+        }
+        if config.run_once {
+            std::process::exit(if result.is_ok() { 0 } else { 1 });
+        }
+        thread::sleep(config.poll_interval);
+    }
+}
+
+fn run_once(
+    rpc: &RpcClient,
+    http: &reqwest::blocking::Client,
+    keeper: &Keypair,
+    config: &KeeperConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (config_address, _) = Pubkey::find_program_address(&[b"config"], &yield_flow::ID);
+    let program_config = {
+        let account = rpc.get_account(&config_address)?;
+        let mut data: &[u8] = &account.data;
+        ProgramConfig::try_deserialize(&mut data)?
+    };
+    // batch_crank_claim odrzucany przy pauzie wypłat lub swapów
+    if program_config.is_paused(PauseTarget::Claims) || program_config.is_paused(PauseTarget::Swaps) {
+        println!("Claims or swaps paused, skipping run");
+        return Ok(());
+    }
+
+    let accounts = CrankAccounts {
+        config: config_address,
+        sol_usd_oracle: program_config.sol_usd_oracle,
+        fee_tiers: Pubkey::find_program_address(&[FeeTiers::SEED], &yield_flow::ID).0,
+        fee_owner: program_config.fees_enabled.then_some(program_config.fee_account),
+    };
+    let program = ProgramSnapshot {
+        fee_tiers: scan::fetch_fee_tiers(rpc)?,
+        config: program_config,
+    };
+    for asset in scan::fetch_assets(rpc)? {
+        if let Err(err) = crank_asset(rpc, http, keeper, config, &accounts, &program, &asset) {
+            eprintln!("Asset {} failed: {}", asset.asset.mint, err);
+        }
+    }
+
+    Ok(())
+}
+
+fn crank_asset(
+    rpc: &RpcClient,
+    http: &reqwest::blocking::Client,
+    keeper: &Keypair,
+    config: &KeeperConfig,
+    accounts: &CrankAccounts,
+    program: &ProgramSnapshot,
+    asset: &RegisteredAsset,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mint = asset.asset.mint;
+    let vault = scan::fetch_vault(rpc, &mint)?;
+    let current_rate = scan::fetch_rate(rpc, &asset.asset)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    let due = scan::fetch_due_positions(rpc, &asset.asset, &vault, current_rate, now)?;
+    if due.is_empty() {
+        println!("No due positions for {} (rate: {} lamports/token)", mint, current_rate);
+        return Ok(());
+    }
+    println!("{} due positions for {}", due.len(), mint);
+
+    // Jedna paczka = jeden swap, więc wszystkie pozycje w niej mają ten sam token wypłaty
+    let mut by_payout_mint: BTreeMap<Pubkey, Vec<_>> = BTreeMap::new();
+    for position in due {
+        by_payout_mint.entry(position.stake.payout_mint).or_default().push(position);
+    }
+
+    for (payout_mint, positions) in by_payout_mint {
+        match scan::fetch_payout_token(rpc, &payout_mint) {
+            Ok(payout_token) if payout_token.enabled => {}
+            Ok(_) => {
+                eprintln!("Payout token {} disabled, skipping {} positions", payout_mint, positions.len());
+                continue;
+            }
+            Err(err) => {
+                eprintln!("Payout token {} unavailable: {}", payout_mint, err);
+                continue;
+            }
+        }
+
+        for batch in positions.chunks(config.batch_size.clamp(1, u8::MAX as usize)) {
+            let yield_lst = scan::batch_yield_lst(
+                program,
+                &vault,
+                batch,
+                &payout_mint,
+                current_rate,
+                now,
+            )?;
+            let route = match route::fetch_route(
+                http,
+                config,
+                &keeper.pubkey(),
+                &vault,
+                &mint,
+                &payout_mint,
+                yield_lst,
+            ) {
+                Ok(route) => route,
+                Err(err) => {
+                    eprintln!("No route for {} of {} -> {}: {}", yield_lst, mint, payout_mint, err);
+                    continue;
+                }
+            };
+
+            let mut instructions = submit::payout_accounts_setup(&keeper.pubkey(), &vault, &payout_mint);
+            for instruction in &route.setup {
+                if !instructions.contains(instruction) {
+                    instructions.push(instruction.clone());
+                }
+            }
+            instructions.push(submit::build_batch_instruction(
+                &keeper.pubkey(),
+                accounts,
+                asset,
+                &vault,
+                &payout_mint,
+                batch,
+                &route,
+            ));
+            match submit::send_with_retries(rpc, keeper, config, &instructions, &route.lookup_tables) {
+                Ok(signature) => println!("Cranked {} positions: {}", batch.len(), signature),
+                Err(err) => eprintln!("Batch of {} positions failed: {}", batch.len(), err),
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+// Trasy swapu LST zysku -> token wypłaty (USDC, USDT, PYUSD, wSOL, ...)
+//
+// Źródło tras wybiera KEEPER_ROUTE_SOURCE (config.rs):
+// - jupiter: trasa z API Jupitera (mainnet)
+// - local: instrukcja route programu localnet-mock ładowanego pod adresem
+//   Jupitera na solana-test-validator - stały kurs KEEPER_LOCAL_ROUTE_RATE,
+//   tokeny wymieniane z pulą PDA ["pool"] (ATA puli dla obu mintów)
+//
+// Proces (jupiter):
+// 1. GET {KEEPER_JUPITER_API}/quote - wycena dokładnej ilości LST zysku
+// 2. POST {KEEPER_JUPITER_API}/swap-instructions - instrukcja swapu dla
+//    skarbca (userPublicKey = PDA Vault), instrukcje przygotowujące
+//    (setupInstructions) i tablice adresów (addressLookupTableAddresses)
+// 3. Dopasowanie kont do programu:
+//    - ATA LST skarbca zastępowane kontem tokenowym skarbca (PDA "vault-lst")
+//    - PDA Vault przekazywane bez podpisu - program podpisuje je w CPI
+// 4. Instrukcje przygotowujące:
+//    - Tworzenie ATA opłaca keeper (PDA Vault nie podpisuje poza programem),
+//      ATA LST skarbca pomijane - trasa używa konta "vault-lst"
+//    - Inne instrukcje wymagające podpisu skarbca są odrzucane
+//
+// Dane trasy odwołują się do kont wyłącznie przez indeksy, więc podmiana
+// adresu konta nie zmienia danych instrukcji.
+
+use std::str::FromStr;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{
+    config::{KeeperConfig, RouteSource},
+    scan::VaultSnapshot,
+};
+
+/// Trasa gotowa do przekazania w remaining_accounts + route_data
+pub struct Route {
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+    /// Instrukcje wykonywane przed batch_crank_claim (np. ATA skarbca)
+    pub setup: Vec<Instruction>,
+    /// Tablice adresów (ALT) trasy dla transakcji v0
+    pub lookup_tables: Vec<Pubkey>,
+}
+
+pub fn fetch_route(
+    http: &reqwest::blocking::Client,
+    config: &KeeperConfig,
+    keeper: &Pubkey,
+    vault: &VaultSnapshot,
+    lst_mint: &Pubkey,
+    payout_mint: &Pubkey,
+    amount: u64,
+) -> Result<Route, Box<dyn std::error::Error>> {
+    match config.route_source {
+        RouteSource::Jupiter => {
+            fetch_jupiter_route(http, config, keeper, vault, lst_mint, payout_mint, amount)
+        }
+        RouteSource::Local => local_route(config, vault, lst_mint, payout_mint, amount),
+    }
+}
+
+fn fetch_jupiter_route(
+    http: &reqwest::blocking::Client,
+    config: &KeeperConfig,
+    keeper: &Pubkey,
+    vault: &VaultSnapshot,
+    lst_mint: &Pubkey,
+    payout_mint: &Pubkey,
+    amount: u64,
+) -> Result<Route, Box<dyn std::error::Error>> {
+    let quote: Value = http
+        .get(format!("{}/quote", config.jupiter_api_url))
+        .query(&[
+            ("inputMint", lst_mint.to_string()),
+            ("outputMint", payout_mint.to_string()),
+            ("amount", amount.to_string()),
+            ("slippageBps", config.route_slippage_bps.to_string()),
+            ("swapMode", "ExactIn".to_string()),
+        ])
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let response: Value = http
+        .post(format!("{}/swap-instructions", config.jupiter_api_url))
+        .json(&json!({
+            "quoteResponse": quote,
+            "userPublicKey": vault.address.to_string(),
+            "wrapAndUnwrapSol": false,
+        }))
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let source_ata = get_associated_token_address(&vault.address, lst_mint);
+    let swap = parse_instruction(&response["swapInstruction"])?;
+
+    let accounts = swap
+        .accounts
+        .into_iter()
+        .map(|account| {
+            let pubkey = if account.pubkey == source_ata { vault.token_account } else { account.pubkey };
+            AccountMeta {
+                pubkey,
+                is_signer: account.is_signer && pubkey != vault.address,
+                is_writable: account.is_writable,
+            }
+        })
+        .collect();
+
+    let mut setup = Vec::new();
+    for value in response["setupInstructions"].as_array().into_iter().flatten() {
+        let instruction = parse_instruction(value)?;
+        if instruction.program_id == spl_associated_token_account::ID {
+            // Konta create: [funder, ata, wallet, mint, system_program, token_program]
+            let [_, ata, wallet, mint, _, token_program] = instruction.accounts.as_slice() else {
+                return Err("Unexpected associated token account instruction".into());
+            };
+            if ata.pubkey != source_ata {
+                setup.push(create_associated_token_account_idempotent(
+                    keeper,
+                    &wallet.pubkey,
+                    &mint.pubkey,
+                    &token_program.pubkey,
+                ));
+            }
+        } else if instruction.accounts.iter().any(|account| account.is_signer && account.pubkey == vault.address) {
+            return Err(format!("Setup instruction of {} needs the vault signature", instruction.program_id).into());
+        } else {
+            setup.push(instruction);
+        }
+    }
+
+    let lookup_tables = response["addressLookupTableAddresses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|address| Ok(Pubkey::from_str(address.as_str().ok_or("Invalid lookup table")?)?))
+        .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+
+    Ok(Route { accounts, data: swap.data, setup, lookup_tables })
+}
+
+/// Instrukcja w formacie API Jupitera (programId, accounts, data w base64)
+fn parse_instruction(value: &Value) -> Result<Instruction, Box<dyn std::error::Error>> {
+    let program_id = Pubkey::from_str(value["programId"].as_str().ok_or("Missing program id")?)?;
+    let mut accounts = Vec::new();
+    for account in value["accounts"].as_array().ok_or("Missing instruction accounts")? {
+        accounts.push(AccountMeta {
+            pubkey: Pubkey::from_str(account["pubkey"].as_str().ok_or("Missing pubkey")?)?,
+            is_signer: account["isSigner"].as_bool().unwrap_or(false),
+            is_writable: account["isWritable"].as_bool().unwrap_or(false),
+        });
+    }
+    let data = STANDARD.decode(value["data"].as_str().ok_or("Missing instruction data")?)?;
+
+    Ok(Instruction { program_id, accounts, data })
+}
+
+fn local_route(
+    config: &KeeperConfig,
+    vault: &VaultSnapshot,
+    lst_mint: &Pubkey,
+    payout_mint: &Pubkey,
+    amount: u64,
+) -> Result<Route, Box<dyn std::error::Error>> {
+    let amount_out = u64::try_from(
+        amount as u128 * config.local_route_rate as u128 / LAMPORTS_PER_SOL as u128,
+    )?;
+    let pool = Pubkey::find_program_address(&[localnet_mock::POOL_SEED], &localnet_mock::ID).0;
+
+    let mut accounts = localnet_mock::accounts::Route {
+        authority: vault.address,
+        source: vault.token_account,
+        destination: get_associated_token_address(&vault.address, payout_mint),
+        pool,
+        pool_source: get_associated_token_address(&pool, lst_mint),
+        pool_destination: get_associated_token_address(&pool, payout_mint),
+        token_program: anchor_spl::token::ID,
+    }
+    .to_account_metas(None);
+    // PDA Vault podpisuje program w CPI
+    for account in accounts.iter_mut().filter(|account| account.pubkey == vault.address) {
+        account.is_signer = false;
+    }
+    let data = localnet_mock::instruction::Route { amount_in: amount, amount_out }.data();
+
+    Ok(Route { accounts, data, setup: Vec::new(), lookup_tables: Vec::new() })
+}
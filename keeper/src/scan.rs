@@ -0,0 +1,416 @@
+// Skanowanie pozycji gotowych do auto-wypłaty
+//
+// Proces (dla każdego aktywa z rejestru LstAsset):
+// 1. Pobranie stanu skarbca aktywa (Vault + saldo konta tokenowego)
+// 2. Pobranie aktualnego kursu z konta puli aktywa (lst::rate_from_pool_data)
+// 3. getProgramAccounts z filtrami: dyskryminator UserStake + rozmiar konta
+//    + mint aktywa pozycji
+// 4. Lokalna ewaluacja ScheduleCalculator::should_payout dla każdej pozycji
+//    w trybie wypłaty Usdc (is_due)
+// 5. batch_yield_lst - ilość LST zysku sprzedawana w jednej paczce
+//    (batch::batch_position_status i batch::settle_batch_position z programu -
+//    te same reguły, kolejność i zaokrąglenia co batch_crank_claim on-chain)
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use yield_flow::{
+    batch::{batch_position_status, pending_dividend, settle_batch_position, CrankClaimStatus},
+    state::{FeeTiers, LstAsset, PayoutMode, PayoutToken, ProgramConfig, UserStake, Vault},
+    utils::{lst, schedule::ScheduleCalculator},
+};
+
+/// Offset pola UserStake.asset (dyskryminator + user)
+const USER_STAKE_ASSET_OFFSET: usize = 8 + 32;
+
+/// Aktywo z rejestru programu
+pub struct RegisteredAsset {
+    pub address: Pubkey,
+    pub asset: LstAsset,
+}
+
+/// Stan skarbca potrzebny do wyceny udziałów
+pub struct VaultSnapshot {
+    pub address: Pubkey,
+    pub token_account: Pubkey,
+    pub total_shares: u64,
+    pub balance: u64,
+}
+
+/// Stan programu potrzebny do rozliczenia paczki (ProgramConfig + tabela progów opłat)
+pub struct ProgramSnapshot {
+    pub config: ProgramConfig,
+    pub fee_tiers: FeeTiers,
+}
+
+/// Pozycja gotowa do wypłaty
+pub struct DuePosition {
+    pub address: Pubkey,
+    pub stake: UserStake,
+}
+
+pub fn fetch_assets(rpc: &RpcClient) -> Result<Vec<RegisteredAsset>, Box<dyn std::error::Error>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(LstAsset::LEN as u64),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &LstAsset::discriminator())),
+        ]),
+        account_config: RpcAccountInfoConfig::default(),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = rpc.get_program_accounts_with_config(&yield_flow::ID, config)?;
+
+    let mut assets = Vec::new();
+    for (address, account) in accounts {
+        match deserialize::<LstAsset>(&account) {
+            Ok(asset) => assets.push(RegisteredAsset { address, asset }),
+            Err(err) => eprintln!("Skipping asset {}: {}", address, err),
+        }
+    }
+
+    Ok(assets)
+}
+
+pub fn fetch_vault(rpc: &RpcClient, mint: &Pubkey) -> Result<VaultSnapshot, Box<dyn std::error::Error>> {
+    let (address, _) = Pubkey::find_program_address(&[Vault::SEED, mint.as_ref()], &yield_flow::ID);
+    let vault = deserialize::<Vault>(&rpc.get_account(&address)?)?;
+    let balance = rpc.get_token_account_balance(&vault.token_account)?.amount.parse()?;
+
+    Ok(VaultSnapshot {
+        address,
+        token_account: vault.token_account,
+        total_shares: vault.total_shares,
+        balance,
+    })
+}
+
+pub fn fetch_fee_tiers(rpc: &RpcClient) -> Result<FeeTiers, Box<dyn std::error::Error>> {
+    let (address, _) = Pubkey::find_program_address(&[FeeTiers::SEED], &yield_flow::ID);
+    deserialize::<FeeTiers>(&rpc.get_account(&address)?)
+}
+
+pub fn fetch_payout_token(
+    rpc: &RpcClient,
+    mint: &Pubkey
+) -> Result<PayoutToken, Box<dyn std::error::Error>> {
+    let (address, _) = Pubkey::find_program_address(&[PayoutToken::SEED, mint.as_ref()], &yield_flow::ID);
+    deserialize::<PayoutToken>(&rpc.get_account(&address)?)
+}
+
+/// Kurs aktywa w lamportach za 1 token
+pub fn fetch_rate(
+    rpc: &RpcClient,
+    asset: &LstAsset
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let account = rpc.get_account(&asset.pool_state)?;
+    let epoch = rpc.get_epoch_info()?.epoch;
+    // Ten sam odczyt co on-chain (lst::read_rate)
+    Ok(lst::rate_from_pool_data(asset, &account.data, epoch)?)
+}
+
+pub fn fetch_due_positions(
+    rpc: &RpcClient,
+    asset: &LstAsset,
+    vault: &VaultSnapshot,
+    current_rate: u64,
+    current_timestamp: i64,
+) -> Result<Vec<DuePosition>, Box<dyn std::error::Error>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(user_stake_filters(&asset.mint)),
+        account_config: RpcAccountInfoConfig::default(),
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = rpc.get_program_accounts_with_config(&yield_flow::ID, config)?;
+
+    let mut due = Vec::new();
+    for (address, account) in accounts {
+        let stake = match deserialize::<UserStake>(&account) {
+            Ok(stake) => stake,
+            Err(err) => {
+                eprintln!("Skipping {}: {}", address, err);
+                continue;
+            }
+        };
+
+        let dividend = pending_dividend(&stake, vault.total_shares, vault.balance, current_rate)?;
+        if is_due(&stake, dividend, current_timestamp)? {
+            due.push(DuePosition { address, stake });
+        }
+    }
+
+    Ok(due)
+}
+
+/// Filtry getProgramAccounts: konta UserStake pozycji w aktywie `mint`
+pub fn user_stake_filters(mint: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(UserStake::LEN as u64),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &UserStake::discriminator())),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(USER_STAKE_ASSET_OFFSET, mint.as_ref())),
+    ]
+}
+
+/// Czy pozycja z niewypłaconą dywidendą `dividend` jest gotowa do cranka
+pub fn is_due(
+    stake: &UserStake,
+    dividend: u64,
+    current_timestamp: i64
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // Pozycje w trybie Sol wypłaca właściciel (liquid unstake), nie crank
+    if stake.payout_mode != PayoutMode::Usdc || dividend == 0 {
+        return Ok(false);
+    }
+
+    Ok(ScheduleCalculator::should_payout(stake, dividend, current_timestamp)?)
+}
+
+/// Łączna ilość LST zysku (po opłatach) sprzedawana w paczce `positions`
+///
+/// Rozliczenie na kopiach stanu programu, skarbca i pozycji - pozycje,
+/// które program pominie (status inny niż Paid), nie wchodzą do sumy.
+pub fn batch_yield_lst(
+    program: &ProgramSnapshot,
+    vault: &VaultSnapshot,
+    positions: &[DuePosition],
+    payout_mint: &Pubkey,
+    current_rate: u64,
+    now: i64,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut config = program.config.clone();
+    let mut vault_state = Vault {
+        mint: Pubkey::default(),
+        token_account: vault.token_account,
+        total_shares: vault.total_shares,
+        bump: 0,
+        token_bump: 0,
+    };
+    let mut available_lst = vault.balance;
+    let mut total_yield_lst = 0u64;
+
+    for position in positions {
+        let mut stake = position.stake.clone();
+        let dividend = pending_dividend(&stake, vault_state.total_shares, available_lst, current_rate)?;
+        if batch_position_status(&stake, dividend, payout_mint, current_rate, now)? != CrankClaimStatus::Paid {
+            continue;
+        }
+
+        let settlement = settle_batch_position(
+            &mut config,
+            &program.fee_tiers,
+            &mut stake,
+            &mut vault_state,
+            available_lst,
+            current_rate,
+            now,
+        )?;
+        available_lst -= settlement.vault_outflow()?;
+        total_yield_lst += settlement.yield_lst;
+    }
+
+    Ok(total_yield_lst)
+}
+
+fn deserialize<T: AccountDeserialize>(account: &Account) -> Result<T, Box<dyn std::error::Error>> {
+    let mut data: &[u8] = &account.data;
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::account::AccountSharedData;
+    use yield_flow::state::{FeeTier, PayoutSchedule};
+
+    const RATE: u64 = 1_100_000_000; // 1.1 SOL za 1 LST
+    const NOW: i64 = 1_700_000_000;
+    const SECONDS_PER_YEAR: i64 = 31_536_000;
+
+    fn stake(asset: Pubkey, payout_mint: Pubkey, shares: u64) -> UserStake {
+        UserStake {
+            user: Pubkey::new_unique(),
+            asset,
+            shares,
+            base_sol_value: 1_000_000_000,
+            last_update: NOW,
+            payout_schedule: PayoutSchedule::Daily,
+            next_payout_date: NOW,
+            auto_claim_enabled: true,
+            payout_mode: PayoutMode::Usdc,
+            payout_mint,
+            payout_bps: 10_000,
+            ..Default::default()
+        }
+    }
+
+    fn program(fees_enabled: bool, fee_rate: u16, management_fee_bps: u16, tiers: Vec<FeeTier>) -> ProgramSnapshot {
+        ProgramSnapshot {
+            config: ProgramConfig {
+                admin: Pubkey::new_unique(),
+                marinade_program: Pubkey::default(),
+                msol_mint: Pubkey::default(),
+                sanglass_program: Pubkey::default(),
+                usdc_mint: Pubkey::default(),
+                fee_account: Pubkey::new_unique(),
+                bump: 0,
+                fees_enabled,
+                fee_rate,
+                keeper_bounty_bps: 0,
+                sol_usd_oracle: Pubkey::default(),
+                msol_sol_oracle: Pubkey::default(),
+                oracle_max_age_secs: 0,
+                oracle_max_conf_bps: 0,
+                oracle_max_slippage_bps: 0,
+                total_fees_collected: 0,
+                management_fee_bps,
+                pending_admin: None,
+                paused: false,
+                deposits_paused: false,
+                swaps_paused: false,
+                claims_paused: false,
+                withdrawals_paused: false,
+            },
+            fee_tiers: FeeTiers { tiers, bump: 0 },
+        }
+    }
+
+    fn vault(total_shares: u64, balance: u64) -> VaultSnapshot {
+        VaultSnapshot {
+            address: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            total_shares,
+            balance,
+        }
+    }
+
+    fn due(stake: UserStake) -> DuePosition {
+        DuePosition { address: Pubkey::new_unique(), stake }
+    }
+
+    fn serialized<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(len);
+        account.try_serialize(&mut data).unwrap();
+        data.resize(len, 0);
+        data
+    }
+
+    fn account_data(data: Vec<u8>) -> AccountSharedData {
+        let mut account = AccountSharedData::new(0, data.len(), &yield_flow::ID);
+        account.set_data(data);
+        account
+    }
+
+    #[test]
+    fn filters_select_positions_of_asset() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let filters = user_stake_filters(&mint);
+        let allows = |account: &AccountSharedData| filters.iter().all(|filter| filter.allows(account));
+
+        assert!(allows(&account_data(serialized(&stake(mint, payout_mint, 1), UserStake::LEN))));
+        // Pozycja innego aktywa
+        assert!(!allows(&account_data(serialized(&stake(Pubkey::new_unique(), payout_mint, 1), UserStake::LEN))));
+        // Konto o innym rozmiarze
+        assert!(!allows(&account_data(serialized(&stake(mint, payout_mint, 1), UserStake::LEN + 1))));
+        // Inny typ konta o tym samym rozmiarze i z mintem pod offsetem aktywa
+        let mut tiers = serialized(&FeeTiers { tiers: vec![], bump: 0 }, UserStake::LEN);
+        tiers[USER_STAKE_ASSET_OFFSET..USER_STAKE_ASSET_OFFSET + 32].copy_from_slice(mint.as_ref());
+        assert!(!allows(&account_data(tiers)));
+    }
+
+    #[test]
+    fn is_due_follows_schedule_and_payout_mode() {
+        let base = stake(Pubkey::new_unique(), Pubkey::new_unique(), 1_000);
+        assert!(is_due(&base, 1, NOW).unwrap());
+        assert!(!is_due(&base, 0, NOW).unwrap());
+        assert!(!is_due(&base, 1, NOW - 1).unwrap());
+
+        let sol = UserStake { payout_mode: PayoutMode::Sol, ..base.clone() };
+        assert!(!is_due(&sol, 1, NOW).unwrap());
+        let disabled = UserStake { auto_claim_enabled: false, ..base.clone() };
+        assert!(!is_due(&disabled, 1, NOW).unwrap());
+        let no_schedule = UserStake { payout_schedule: PayoutSchedule::Disabled, ..base.clone() };
+        assert!(!is_due(&no_schedule, 1, NOW).unwrap());
+        let minimum = UserStake { min_dividend_amount: 100, ..base };
+        assert!(!is_due(&minimum, 99, NOW).unwrap());
+        assert!(is_due(&minimum, 100, NOW).unwrap());
+    }
+
+    #[test]
+    fn batch_yield_without_fees() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let positions = [due(stake(mint, payout_mint, 1_000)), due(stake(mint, payout_mint, 1_000))];
+
+        // Każda pozycja: 1_000 LST * (1.1 - 1.0) = 100 lamportów zysku = 90 LST
+        let total = batch_yield_lst(&program(false, 1_000, 500, vec![]), &vault(2_000, 2_000), &positions, &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, 180);
+    }
+
+    #[test]
+    fn batch_yield_after_protocol_fee_and_payout_bps() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let positions = [
+            due(stake(mint, payout_mint, 1_000)),
+            due(UserStake { payout_bps: 5_000, ..stake(mint, payout_mint, 1_000) }),
+        ];
+
+        // Stała stawka 10%: 90 - 9, połowa zysku: 45 - 4
+        let fees = program(true, 1_000, 0, vec![]);
+        let total = batch_yield_lst(&fees, &vault(2_000, 2_000), &positions, &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, 81 + 41);
+
+        // Próg od 1_000 lamportów wartości pozycji: 5%
+        let tiers = vec![FeeTier { min_value: 0, fee_bps: 1_000 }, FeeTier { min_value: 1_000, fee_bps: 500 }];
+        let fees = program(true, 2_000, 0, tiers);
+        let total = batch_yield_lst(&fees, &vault(2_000, 2_000), &positions, &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, (90 - 4) + (45 - 2));
+    }
+
+    #[test]
+    fn batch_yield_after_management_fee() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let position = UserStake { last_update: NOW - SECONDS_PER_YEAR, ..stake(mint, payout_mint, 1_000) };
+
+        // 5% rocznie od 1_000 lamportów kapitału = 50 lamportów = 45 LST;
+        // zostaje 955 LST z zyskiem 95 lamportów = 86 LST
+        let total = batch_yield_lst(&program(true, 0, 500, vec![]), &vault(1_000, 1_000), &[due(position)], &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, 86);
+    }
+
+    #[test]
+    fn batch_yield_skips_positions_below_minimum_after_management_fee() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let positions = [
+            // Zysk 100 lamportów, po opłacie 95 - poniżej minimum
+            due(UserStake {
+                last_update: NOW - SECONDS_PER_YEAR,
+                min_dividend_amount: 100,
+                ..stake(mint, payout_mint, 1_000)
+            }),
+            // Zysk 2 lamporty (1 LST), po 10 latach opłaty 1 lamport - 0 LST
+            due(UserStake { last_update: NOW - 10 * SECONDS_PER_YEAR, ..stake(mint, payout_mint, 20) }),
+            due(stake(mint, payout_mint, 1_000)),
+        ];
+
+        let total = batch_yield_lst(&program(true, 0, 500, vec![]), &vault(2_020, 2_020), &positions, &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, 90);
+    }
+
+    #[test]
+    fn batch_yield_skips_positions_program_would_skip() {
+        let (mint, payout_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let positions = [
+            due(stake(mint, Pubkey::new_unique(), 1_000)),
+            due(UserStake { payout_mode: PayoutMode::Sol, ..stake(mint, payout_mint, 1_000) }),
+            due(UserStake { next_payout_date: NOW + 1, ..stake(mint, payout_mint, 1_000) }),
+            due(UserStake { min_dividend_amount: 101, ..stake(mint, payout_mint, 1_000) }),
+            due(stake(mint, payout_mint, 1_000)),
+        ];
+
+        let total = batch_yield_lst(&program(false, 0, 0, vec![]), &vault(5_000, 5_000), &positions, &payout_mint, RATE, NOW).unwrap();
+        assert_eq!(total, 90);
+    }
+}
@@ -0,0 +1,161 @@
+// Wysyłanie transakcji batch_crank_claim
+//
+// Każda transakcja (v0) zawiera:
+// - ComputeBudget: limit CU + priority fee (micro-lamporty za CU)
+// - Idempotentne utworzenie ATA tokenu wypłaty skarbca (cel swapu) i keepera
+//   (nagroda) - opłaca keeper
+// - Instrukcje przygotowujące trasy (route.rs)
+// - batch_crank_claim z grupami [user_stake, owner_payout, ATA beneficjentów...]
+//   w remaining_accounts, a po nich kontami trasy Jupitera (route.rs)
+//
+// Tablice adresów (ALT) trasy pobierane są raz na paczkę - bez nich konta
+// tras Jupitera nie mieszczą się w limicie rozmiaru transakcji
+//
+// Ponowienia:
+// - Każda próba pobiera świeży blockhash
+// - Odstęp między próbami rośnie liniowo (1s, 2s, 3s, ...)
+
+use std::{thread, time::Duration};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_address_lookup_table_program::state::AddressLookupTable;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    address_lookup_table_account::AddressLookupTableAccount,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use yield_flow::state::PayoutToken;
+
+use crate::{
+    config::KeeperConfig,
+    route::Route,
+    scan::{DuePosition, RegisteredAsset, VaultSnapshot},
+};
+
+/// Adresy wspólne dla wszystkich transakcji cranka
+pub struct CrankAccounts {
+    pub config: Pubkey,
+    pub sol_usd_oracle: Pubkey,
+    pub fee_tiers: Pubkey,
+    /// Właściciel kont opłat protokołu (None gdy opłaty wyłączone)
+    pub fee_owner: Option<Pubkey>,
+}
+
+pub fn build_batch_instruction(
+    keeper: &Pubkey,
+    accounts: &CrankAccounts,
+    asset: &RegisteredAsset,
+    vault: &VaultSnapshot,
+    payout_mint: &Pubkey,
+    positions: &[DuePosition],
+    route: &Route,
+) -> Instruction {
+    let mut metas = yield_flow::accounts::BatchCrankClaim {
+        keeper: *keeper,
+        config: accounts.config,
+        fee_tiers: accounts.fee_tiers,
+        asset: asset.address,
+        vault: vault.address,
+        vault_lst: vault.token_account,
+        fee_lst: accounts
+            .fee_owner
+            .map(|owner| get_associated_token_address(&owner, &asset.asset.mint)),
+        pool_state: asset.asset.pool_state,
+        payout_token: Pubkey::find_program_address(
+            &[PayoutToken::SEED, payout_mint.as_ref()],
+            &yield_flow::ID,
+        ).0,
+        keeper_payout: get_associated_token_address(keeper, payout_mint),
+        vault_payout: get_associated_token_address(&vault.address, payout_mint),
+        lst_mint: asset.asset.mint,
+        payout_mint: *payout_mint,
+        sol_usd_oracle: accounts.sol_usd_oracle,
+        lst_sol_oracle: asset.asset.lst_sol_oracle,
+        jupiter_program: yield_flow::utils::jupiter::JUPITER_PROGRAM_ID,
+        token_program: anchor_spl::token::ID,
+    }
+    .to_account_metas(None);
+
+    for position in positions {
+        metas.push(AccountMeta::new(position.address, false));
+        metas.push(AccountMeta::new(
+            get_associated_token_address(&position.stake.user, payout_mint),
+            false,
+        ));
+        for beneficiary in position.stake.beneficiaries.iter() {
+            metas.push(AccountMeta::new(
+                get_associated_token_address(&beneficiary.wallet, payout_mint),
+                false,
+            ));
+        }
+    }
+    metas.extend(route.accounts.iter().cloned());
+
+    Instruction {
+        program_id: yield_flow::ID,
+        accounts: metas,
+        data: yield_flow::instruction::BatchCrankClaim {
+            position_count: positions.len() as u8,
+            route_data: route.data.clone(),
+        }
+        .data(),
+    }
+}
+
+/// ATA tokenu wypłaty wymagane przez batch_crank_claim (vault_payout, keeper_payout)
+pub fn payout_accounts_setup(keeper: &Pubkey, vault: &VaultSnapshot, payout_mint: &Pubkey) -> Vec<Instruction> {
+    [vault.address, *keeper]
+        .iter()
+        .map(|owner| {
+            create_associated_token_account_idempotent(keeper, owner, payout_mint, &anchor_spl::token::ID)
+        })
+        .collect()
+}
+
+pub fn send_with_retries(
+    rpc: &RpcClient,
+    keeper: &Keypair,
+    config: &KeeperConfig,
+    instructions: &[Instruction],
+    lookup_tables: &[Pubkey],
+) -> Result<Signature, Box<dyn std::error::Error>> {
+    let lookup_tables = lookup_tables
+        .iter()
+        .map(|address| {
+            let account = rpc.get_account(address)?;
+            let table = AddressLookupTable::deserialize(&account.data)?;
+            Ok(AddressLookupTableAccount { key: *address, addresses: table.addresses.to_vec() })
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    let mut all_instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(config.compute_units),
+        ComputeBudgetInstruction::set_compute_unit_price(config.priority_fee_micro_lamports),
+    ];
+    all_instructions.extend_from_slice(instructions);
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let blockhash = rpc.get_latest_blockhash()?;
+        let message = v0::Message::try_compile(&keeper.pubkey(), &all_instructions, &lookup_tables, blockhash)?;
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[keeper])?;
+
+        match rpc.send_and_confirm_transaction(&tx) {
+            Ok(signature) => return Ok(signature),
+            Err(err) if attempt < config.max_retries => {
+                eprintln!("Attempt {}/{} failed: {}", attempt, config.max_retries, err);
+                thread::sleep(Duration::from_secs(attempt as u64));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
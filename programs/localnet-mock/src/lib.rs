@@ -0,0 +1,154 @@
+// localnet-mock - zamienniki programów zewnętrznych dla solana-test-validator
+//
+// Program ładowany w genesis walidatora pod adresem Jupiter v6
+// (Anchor.toml [[test.genesis]]), dzięki czemu YieldFlow wykonuje swapy
+// bez zmian w kodzie on-chain. Nigdy nie jest wdrażany na devnet/mainnet.
+//
+// Główne funkcjonalności:
+// 1. route() - swap po stałym kursie ustalonym przez klienta (keeper, tryb
+//    KEEPER_ROUTE_SOURCE=local)
+//    - Pobiera amount_in tokenu wejściowego do puli (podpis authority -
+//      w CPI z YieldFlow jest nim PDA Vault)
+//    - Wypłaca amount_out tokenu wyjściowego z puli (PDA ["pool"])
+//    - Pula musi być wcześniej zasilona tokenem wyjściowym
+//
+// 2. create_stake_pool() / set_stake_pool_rate() - konto w układzie
+//    SPL StakePool (utils/stake_pool.rs YieldFlow) ze sterowanym kursem
+//    - Aktywo rejestrowane z RateReader::SplStakePool i pool_program = adres
+//      tego programu
+//    - Podniesienie kursu tworzy dywidendę do wypłaty przez keepera
+//    - last_update_epoch = u64::MAX, więc kurs jest zawsze aktualny
+//
+// Adresy:
+// - Pula swapu: PDA ["pool"], konta tokenowe puli to ATA tego PDA
+// - Pula stakingu: PDA ["stake-pool", pool_mint]
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const STAKE_POOL_SEED: &[u8] = b"stake-pool";
+
+// Układ konta StakePool - offsety jak w utils/stake_pool.rs YieldFlow
+const ACCOUNT_TYPE_STAKE_POOL: u8 = 1;
+const POOL_MINT_OFFSET: usize = 162;
+const TOTAL_LAMPORTS_OFFSET: usize = 258;
+const POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+const LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+pub const STAKE_POOL_LEN: usize = LAST_UPDATE_EPOCH_OFFSET + 8;
+
+#[program]
+pub mod localnet_mock {
+    use super::*;
+
+    /// Swap po kursie z danych instrukcji: amount_in do puli, amount_out z puli
+    pub fn route(ctx: Context<Route>, amount_in: u64, amount_out: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.source.to_account_info(),
+                    to: ctx.accounts.pool_source.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                },
+            ),
+            amount_in,
+        )?;
+
+        let pool_seeds: &[&[u8]] = &[POOL_SEED, &[ctx.bumps.pool]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_destination.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            amount_out,
+        )?;
+
+        msg!("Mock route: {} in, {} out", amount_in, amount_out);
+        Ok(())
+    }
+
+    /// Tworzy konto puli w układzie SPL StakePool dla mintu LST
+    pub fn create_stake_pool(
+        ctx: Context<CreateStakePool>,
+        total_lamports: u64,
+        pool_token_supply: u64,
+    ) -> Result<()> {
+        let mut data = ctx.accounts.stake_pool.try_borrow_mut_data()?;
+        data[0] = ACCOUNT_TYPE_STAKE_POOL;
+        data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32]
+            .copy_from_slice(&ctx.accounts.pool_mint.key().to_bytes());
+        write_u64(&mut data, LAST_UPDATE_EPOCH_OFFSET, u64::MAX);
+        write_rate(&mut data, total_lamports, pool_token_supply);
+        Ok(())
+    }
+
+    /// Ustawia kurs puli (total_lamports / pool_token_supply)
+    pub fn set_stake_pool_rate(
+        ctx: Context<SetStakePoolRate>,
+        total_lamports: u64,
+        pool_token_supply: u64,
+    ) -> Result<()> {
+        let mut data = ctx.accounts.stake_pool.try_borrow_mut_data()?;
+        write_rate(&mut data, total_lamports, pool_token_supply);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Route<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, token::authority = authority)]
+    pub source: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub destination: Account<'info, TokenAccount>,
+    /// CHECK: PDA podpisujące wypłaty z puli
+    #[account(seeds = [POOL_SEED], bump)]
+    pub pool: AccountInfo<'info>,
+    #[account(mut, token::mint = source.mint, token::authority = pool)]
+    pub pool_source: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = destination.mint, token::authority = pool)]
+    pub pool_destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateStakePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Surowe dane w układzie SPL StakePool
+    #[account(
+        init,
+        payer = payer,
+        space = STAKE_POOL_LEN,
+        seeds = [STAKE_POOL_SEED, pool_mint.key().as_ref()],
+        bump
+    )]
+    pub stake_pool: AccountInfo<'info>,
+    pub pool_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakePoolRate<'info> {
+    /// CHECK: Surowe dane w układzie SPL StakePool
+    #[account(mut, seeds = [STAKE_POOL_SEED, pool_mint.key().as_ref()], bump)]
+    pub stake_pool: AccountInfo<'info>,
+    pub pool_mint: Account<'info, Mint>,
+}
+
+fn write_rate(data: &mut [u8], total_lamports: u64, pool_token_supply: u64) {
+    write_u64(data, TOTAL_LAMPORTS_OFFSET, total_lamports);
+    write_u64(data, POOL_TOKEN_SUPPLY_OFFSET, pool_token_supply);
+}
+
+fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
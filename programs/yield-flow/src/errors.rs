@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+// Kody błędów Anchor wynikają z pozycji wariantu - nowe błędy dopisywane
+// wyłącznie na końcu enuma (nowa grupa lub koniec ostatniej grupy)
 #[error_code]
 pub enum ErrorCode {
     // Błędy ogólne
@@ -64,4 +66,154 @@ pub enum ErrorCode {
 
     #[msg("Custom interval must be positive")]
     InvalidCustomInterval,
+
+    // Błędy skarbca
+    #[msg("Invalid mSOL mint")]
+    InvalidMsolMint,
+
+    #[msg("Vault account mismatch")]
+    VaultMismatch,
+
+    #[msg("Insufficient vault shares")]
+    InsufficientShares,
+
+    // Błędy harmonogramu
+    #[msg("Invalid hour of day (must be 0-23)")]
+    InvalidHourOfDay,
+
+    #[msg("Invalid UTC offset (must be between -720 and 840 minutes)")]
+    InvalidUtcOffset,
+
+    // Błędy keepera
+    #[msg("Keeper bounty exceeds maximum")]
+    InvalidKeeperBounty,
+
+    // Błędy Jupiter
+    #[msg("Invalid Jupiter program")]
+    InvalidJupiterProgram,
+
+    #[msg("Slippage exceeded")]
+    SlippageExceeded,
+
+    #[msg("Swap consumed more input than allowed")]
+    SwapInputExceeded,
+
+    #[msg("Invalid slippage (must be at most 10000 bps)")]
+    InvalidSlippage,
+
+    // Błędy wyroczni
+    #[msg("Invalid oracle account")]
+    InvalidOracleAccount,
+
+    #[msg("Invalid oracle configuration")]
+    InvalidOracleConfig,
+
+    #[msg("Oracle price is stale")]
+    StaleOraclePrice,
+
+    #[msg("Oracle price must be positive")]
+    InvalidOraclePrice,
+
+    #[msg("Oracle confidence interval too wide")]
+    OracleConfidenceTooWide,
+
+    // Błędy wypłat w SOL
+    #[msg("Liquid unstake fee exceeds the configured maximum")]
+    UnstakeFeeTooHigh,
+
+    #[msg("Invalid max unstake fee (must be at most 10000 bps)")]
+    InvalidUnstakeFee,
+
+    #[msg("Instruction does not match the position's payout mode")]
+    PayoutModeMismatch,
+
+    #[msg("Unstake ticket is not ready to claim yet")]
+    TicketNotReady,
+
+    // Błędy rejestru aktywów LST
+    #[msg("Invalid LST asset for this position")]
+    InvalidLstAsset,
+
+    #[msg("LST asset is disabled")]
+    LstAssetDisabled,
+
+    #[msg("Operation not supported for this LST asset")]
+    UnsupportedLstAsset,
+
+    #[msg("Invalid stake pool account")]
+    InvalidStakePool,
+
+    #[msg("Stake pool not updated for the current epoch")]
+    StakePoolNotUpdated,
+
+    #[msg("Invalid LST mint")]
+    InvalidLstMint,
+
+    // Błędy tokenów wypłaty
+    #[msg("Invalid payout token mint")]
+    InvalidPayoutMint,
+
+    #[msg("Payout token is disabled")]
+    PayoutTokenDisabled,
+
+    // Błędy beneficjentów
+    #[msg("Invalid beneficiaries (max count, non-zero bps summing to 100%, unique wallets)")]
+    InvalidBeneficiaries,
+
+    #[msg("Beneficiary token account does not match the beneficiary list")]
+    InvalidBeneficiaryAccount,
+
+    // Błędy kapitalizacji
+    #[msg("Auto-compound is disabled for this position")]
+    AutoCompoundDisabled,
+
+    #[msg("Invalid projection parameters")]
+    InvalidProjection,
+
+    #[msg("Invalid payout share (must be between 1 and 10000 bps)")]
+    InvalidPayoutBps,
+
+    // Błędy opłat
+    #[msg("Invalid fee rate (must be at most 2000 bps)")]
+    InvalidFeeRate,
+
+    #[msg("Invalid protocol fee account")]
+    InvalidFeeAccount,
+
+    #[msg("Invalid fee tiers (max count, first from 0, ascending thresholds, fee at most 2000 bps)")]
+    InvalidFeeTiers,
+
+    #[msg("Management fee exceeds maximum (500 bps per year)")]
+    InvalidManagementFee,
+
+    // Błędy administracji
+    #[msg("Pending admin must differ from the current admin")]
+    InvalidPendingAdmin,
+
+    #[msg("No pending admin transfer")]
+    NoPendingAdmin,
+
+    #[msg("Role holder cannot be the default address (use revoke_role)")]
+    InvalidRoleHolder,
+
+    #[msg("Invalid config change (new address cannot be the default address)")]
+    InvalidConfigChange,
+
+    #[msg("Config change timelock has not elapsed yet")]
+    TimelockNotElapsed,
+
+    #[msg("Operation is paused")]
+    OperationPaused,
+
+    #[msg("Beneficiaries are only supported for token payouts (Usdc payout mode)")]
+    BeneficiariesRequireTokenPayout,
+
+    #[msg("Sanglass program does not match the configured address")]
+    SanglassProgramMismatch,
+
+    #[msg("Oracle addresses can only be changed through the config timelock")]
+    OracleChangeRequiresTimelock,
+
+    #[msg("Swap route uses the vault authority outside the source token account")]
+    InvalidSwapRoute,
 }
\ No newline at end of file
@@ -5,59 +5,560 @@
 //    - Tworzy główne konto konfiguracyjne
 //    - Ustawia administratora programu
 //    - Zapamiętuje kluczowe adresy (program Marinade, mint mSOL)
+//    - Ustawia stawki opłat (opłaty włączane dopiero po ustawieniu fee_account)
+//    - Tworzy pustą tabelę progów opłaty (FeeTiers) wymaganą przez wypłaty
 //
-// 2. Zarządzanie uprawnieniami admina
-//    - Pozwala na zmianę administratora programu
-//    - Wymaga podpisu obecnego admina
+// 2. Zarządzanie uprawnieniami admina (zmiana dwuetapowa)
+//    - Obecny admin proponuje nowego (pending_admin w ProgramConfig)
+//    - Nowy admin przejmuje uprawnienia własnym podpisem (accept)
+//    - Obecny admin może anulować propozycję przed akceptacją
+//    - Każdy krok emituje event
+//
+// 3. Nagroda keepera (rola fee_manager)
+//    - Ustawia keeper_bounty_bps (limit MAX_KEEPER_BOUNTY_BPS)
+//
+// 4. Wyrocznie Pyth (admin)
+//    - Rejestruje konta cen SOL/USD i mSOL/SOL przy pierwszej konfiguracji -
+//      późniejsza zmiana adresów tylko przez timelock (ConfigField::SolUsdOracle,
+//      ConfigField::MsolSolOracle)
+//    - Ustawia maksymalny wiek ceny i przedział ufności
+//    - Ustawia tolerancję slippage swapów wykonywanych przez keeperów
+//      (limit MAX_ORACLE_SLIPPAGE_BPS)
+//
+// 5. Rejestr aktywów LST
+//    - Rejestruje aktywo (mint, program i stan puli, sposób odczytu kursu,
+//      wyrocznia LST/SOL) - tylko admin, adresy puli i wyroczni aktywa są
+//      niezmienne po rejestracji; kurs odczytywany przy rejestracji jako walidacja
+//    - Włącza / wyłącza aktywo dla nowych pozycji i wpłat (rola registry_manager)
+//
+// 6. Lista dozwolonych tokenów wypłaty (rola registry_manager)
+//    - Dodaje token (mint + sposób wyceny dla podłogi z wyroczni)
+//    - Włącza / wyłącza token (wyłączony nie może być wybrany ani wypłacany)
+//
+// 7. Opłata protokołu (rola fee_manager)
+//    - Włącza / wyłącza opłatę, ustawia fee_rate (limit MAX_PROTOCOL_FEE_BPS)
+//    - Roczna opłata za zarządzanie (limit MAX_MANAGEMENT_FEE_BPS)
+//    - Właściciel kont opłat (fee_account) zmieniany przez admina z opóźnieniem
+//      (timelock::queue_handler)
+//    - Tabela progów opłaty (FeeTiers): wartość pozycji w SOL -> fee bps,
+//      pusta tabela = stała stawka fee_rate (stawki progów też do MAX_PROTOCOL_FEE_BPS)
+//
+// 8. Wstrzymanie operacji (rola pauser)
+//    - Pauza globalna lub pojedynczych grup: depozyty, swapy, wypłaty
+//      dywidend, wypłaty kapitału i do Sanglass
+//    - Pauza globalna lub dywidend nie blokuje wypłaty kapitału
+//      (withdraw_principal wypłaca wtedy sam kapitał, bez rozliczania dywidendy)
 //
 // Struktury:
 // - InitializeProgram: Konta wymagane do inicjalizacji
-// - UpdateAdmin: Konta wymagane do zmiany admina
+// - ProposeAdmin / AcceptAdmin / CancelAdminTransfer: Konta wymagane do zmiany admina
+// - SetPaused: Konta wymagane do wstrzymania / wznowienia operacji
+// - SetKeeperBounty: Konta wymagane do zmiany nagrody keepera
+// - SetOracles: Konta wymagane do rejestracji wyroczni
+// - RegisterLstAsset: Konta wymagane do rejestracji aktywa LST
+// - SetLstAssetEnabled: Konta wymagane do włączenia / wyłączenia aktywa
+// - AddPayoutToken: Konta wymagane do dodania tokenu wypłaty
+// - SetPayoutTokenEnabled: Konta wymagane do włączenia / wyłączenia tokenu wypłaty
+// - UpdateProgramParams: Konta wymagane do zmiany parametrów opłat
+// - InitializeFeeTiers / SetFeeTiers: Konta wymagane do utworzenia / zmiany tabeli progów
 //
 // Funkcje handlerów:
 // - initialize_program_handler: Wykonuje inicjalizację
-// - update_admin_handler: Aktualizuje administratora
+// - propose_admin_handler: Proponuje nowego administratora
+// - accept_admin_handler: Przekazuje uprawnienia zaproponowanemu administratorowi
+// - cancel_admin_transfer_handler: Anuluje propozycję zmiany administratora
+// - set_paused_handler: Ustawia flagę pauzy (globalną lub grupy operacji)
+// - set_keeper_bounty_handler: Aktualizuje nagrodę keepera
+// - set_oracles_handler: Aktualizuje wyrocznie i ich limity
+// - register_lst_asset_handler: Dodaje aktywo do rejestru
+// - set_lst_asset_enabled_handler: Zmienia dostępność aktywa
+// - add_payout_token_handler: Dodaje token do listy dozwolonych
+// - set_payout_token_enabled_handler: Zmienia dostępność tokenu wypłaty
+// - update_params_handler: Aktualizuje parametry opłat protokołu
+// - initialize_fee_tiers_handler: Tworzy pustą tabelę progów opłaty dla programów
+//   zainicjalizowanych przed utworzeniem tabeli w initialize_program
+// - set_fee_tiers_handler: Zastępuje progi opłaty
 //
 // Bezpieczeństwo:
-// - Wszystkie operacje wymagają podpisu admina
+// - Operacje wymagają podpisu admina lub posiadacza roli z tabeli Roles
+// - Zmiana admina tylko przez admina, krytyczne adresy przez timelock
 // - Inicjalizacja może nastąpić tylko raz
-// - Zmiana admina wymaga podpisu obecnego admina
+// - Zmiana admina wymaga podpisu obecnego admina (propozycja) i nowego (akceptacja)
 
 use anchor_lang::prelude::*;
-use crate::{state::ProgramConfig, errors::ErrorCode};
+use anchor_spl::token::Mint;
+use crate::{
+    state::{
+        FeeTier, FeeTiers, LstAsset, PauseTarget, PayoutQuote, PayoutToken, ProgramConfig,
+        ProgramParams, RateReader, Role, Roles
+    },
+    utils::{
+        constants::{
+            MAX_BPS, MAX_FEE_TIERS, MAX_KEEPER_BOUNTY_BPS, MAX_MANAGEMENT_FEE_BPS,
+            MAX_ORACLE_SLIPPAGE_BPS, MAX_PROTOCOL_FEE_BPS
+        },
+        lst
+    },
+    errors::ErrorCode
+};
 
 #[derive(Accounts)]
 pub struct InitializeProgram<'info> {
-    #[account(init, payer = admin, space = ProgramConfig::LEN)]
+    #[account(init, payer = admin, space = ProgramConfig::LEN, seeds = [b"config"], bump)]
     pub config: Account<'info, ProgramConfig>,
     #[account(mut)]
     pub admin: Signer<'info>,
+    /// CHECK: Adres programu Marinade zapisywany w konfiguracji
     pub marinade_program: AccountInfo<'info>,
+    /// CHECK: Adres mintu mSOL zapisywany w konfiguracji
     pub msol_mint: AccountInfo<'info>,
+    /// Pusta tabela progów (stała stawka fee_rate) - wymagana przez wypłaty
+    #[account(
+        init,
+        payer = admin,
+        space = FeeTiers::LEN,
+        seeds = [FeeTiers::SEED],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateAdmin<'info> {
-    #[account(mut, has_one = admin)]
+pub struct ProposeAdmin<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
     pub config: Account<'info, ProgramConfig>,
     pub admin: Signer<'info>,
     pub new_admin: SystemAccount<'info>,
 }
 
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(new_admin.key()) @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::Pauser, &pauser.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetKeeperBounty<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::FeeManager, &fee_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracles<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+    /// CHECK: Konto ceny Pyth SOL/USD, weryfikowane przy odczycie
+    pub sol_usd_oracle: AccountInfo<'info>,
+    /// CHECK: Konto ceny Pyth mSOL/SOL, weryfikowane przy odczycie
+    pub msol_sol_oracle: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterLstAsset<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = LstAsset::LEN,
+        seeds = [LstAsset::SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub asset: Account<'info, LstAsset>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: Weryfikowane odczytem kursu w lst::read_rate
+    pub pool_state: AccountInfo<'info>,
+    /// CHECK: Konto ceny Pyth LST/SOL, weryfikowane przy odczycie
+    pub lst_sol_oracle: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLstAssetEnabled<'info> {
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RegistryManager, &registry_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub registry_manager: Signer<'info>,
+    #[account(mut, seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+}
+
+#[derive(Accounts)]
+pub struct AddPayoutToken<'info> {
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RegistryManager, &registry_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(mut)]
+    pub registry_manager: Signer<'info>,
+    #[account(
+        init,
+        payer = registry_manager,
+        space = PayoutToken::LEN,
+        seeds = [PayoutToken::SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+    pub mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPayoutTokenEnabled<'info> {
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::RegistryManager, &registry_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub registry_manager: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PayoutToken::SEED, payout_token.mint.as_ref()],
+        bump = payout_token.bump
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProgramParams<'info> {
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::FeeManager, &fee_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub fee_manager: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeFeeTiers<'info> {
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::FeeManager, &fee_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    #[account(mut)]
+    pub fee_manager: Signer<'info>,
+    #[account(
+        init,
+        payer = fee_manager,
+        space = FeeTiers::LEN,
+        seeds = [FeeTiers::SEED],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    #[account(
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::FeeManager, &fee_manager.key()) @ ErrorCode::Unauthorized
+    )]
+    pub roles: Account<'info, Roles>,
+    pub fee_manager: Signer<'info>,
+    #[account(mut, seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub target: PauseTarget,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
 pub fn initialize_program_handler(
     ctx: Context<InitializeProgram>,
-    bump: u8,
+    params: ProgramParams,
 ) -> Result<()> {
+    require!(params.fee_rate <= MAX_PROTOCOL_FEE_BPS, ErrorCode::InvalidFeeRate);
+    require!(
+        params.management_fee_bps <= MAX_MANAGEMENT_FEE_BPS,
+        ErrorCode::InvalidManagementFee
+    );
+    // fee_account ustawiany później przez timelock - opłaty startują wyłączone
+    require!(!params.fees_enabled, ErrorCode::InvalidFeeAccount);
+
     let config = &mut ctx.accounts.config;
     config.admin = ctx.accounts.admin.key();
     config.marinade_program = ctx.accounts.marinade_program.key();
     config.msol_mint = ctx.accounts.msol_mint.key();
-    config.bump = bump;
+    config.fee_rate = params.fee_rate;
+    config.management_fee_bps = params.management_fee_bps;
+    config.bump = ctx.bumps.config;
+
+    let fee_tiers = &mut ctx.accounts.fee_tiers;
+    fee_tiers.tiers = Vec::new();
+    fee_tiers.bump = ctx.bumps.fee_tiers;
+
+    msg!("Program initialized (admin: {})", config.admin);
+    Ok(())
+}
+
+pub fn propose_admin_handler(ctx: Context<ProposeAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let new_admin = ctx.accounts.new_admin.key();
+    require_keys_neq!(new_admin, config.admin, ErrorCode::InvalidPendingAdmin);
+
+    config.pending_admin = Some(new_admin);
+    msg!("Admin transfer proposed: {} -> {}", config.admin, new_admin);
+    emit!(AdminTransferProposed {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+    Ok(())
+}
+
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = config.admin;
+    config.admin = ctx.accounts.new_admin.key();
+    config.pending_admin = None;
+
+    msg!("Admin transfer accepted: {} -> {}", previous_admin, config.admin);
+    emit!(AdminTransferAccepted {
+        previous_admin,
+        admin: config.admin,
+    });
     Ok(())
 }
 
-pub fn update_admin_handler(ctx: Context<UpdateAdmin>) -> Result<()> {
-    ctx.accounts.config.admin = ctx.accounts.new_admin.key();
+pub fn cancel_admin_transfer_handler(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let pending_admin = config.pending_admin.take().ok_or(ErrorCode::NoPendingAdmin)?;
+
+    msg!("Admin transfer to {} cancelled", pending_admin);
+    emit!(AdminTransferCancelled {
+        admin: config.admin,
+        pending_admin,
+    });
+    Ok(())
+}
+
+pub fn set_paused_handler(ctx: Context<SetPaused>, target: PauseTarget, paused: bool) -> Result<()> {
+    ctx.accounts.config.set_paused(target, paused);
+    msg!("Pause {:?}: {}", target, paused);
+    emit!(PauseChanged { target, paused });
+    Ok(())
+}
+
+pub fn set_keeper_bounty_handler(ctx: Context<SetKeeperBounty>, bounty_bps: u16) -> Result<()> {
+    require!(bounty_bps <= MAX_KEEPER_BOUNTY_BPS, ErrorCode::InvalidKeeperBounty);
+    ctx.accounts.config.keeper_bounty_bps = bounty_bps;
+    msg!("Keeper bounty set to {} bps", bounty_bps);
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn set_oracles_handler(
+    ctx: Context<SetOracles>,
+    max_age_secs: u64,
+    max_conf_bps: u16,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    require!(
+        max_age_secs > 0 && max_conf_bps <= MAX_BPS && max_slippage_bps <= MAX_ORACLE_SLIPPAGE_BPS,
+        ErrorCode::InvalidOracleConfig
+    );
+
+    let config = &mut ctx.accounts.config;
+    let sol_usd_oracle = ctx.accounts.sol_usd_oracle.key();
+    let msol_sol_oracle = ctx.accounts.msol_sol_oracle.key();
+    // Adresy ustawiane bezpośrednio tylko przy pierwszej konfiguracji - zmiana przez timelock
+    require!(
+        config.sol_usd_oracle == Pubkey::default() || config.sol_usd_oracle == sol_usd_oracle,
+        ErrorCode::OracleChangeRequiresTimelock
+    );
+    require!(
+        config.msol_sol_oracle == Pubkey::default() || config.msol_sol_oracle == msol_sol_oracle,
+        ErrorCode::OracleChangeRequiresTimelock
+    );
+    config.sol_usd_oracle = sol_usd_oracle;
+    config.msol_sol_oracle = msol_sol_oracle;
+    config.oracle_max_age_secs = max_age_secs;
+    config.oracle_max_conf_bps = max_conf_bps;
+    config.oracle_max_slippage_bps = max_slippage_bps;
+
+    msg!(
+        "Oracles set: SOL/USD {}, mSOL/SOL {} (max age: {}s, max conf: {} bps, max slippage: {} bps)",
+        config.sol_usd_oracle,
+        config.msol_sol_oracle,
+        max_age_secs,
+        max_conf_bps,
+        max_slippage_bps
+    );
+    Ok(())
+}
+
+pub fn register_lst_asset_handler(
+    ctx: Context<RegisterLstAsset>,
+    rate_reader: RateReader,
+    pool_program: Pubkey,
+) -> Result<()> {
+    lst::verify_pool_program(&ctx.accounts.config, rate_reader, &pool_program)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.mint = ctx.accounts.mint.key();
+    asset.rate_reader = rate_reader;
+    asset.pool_program = pool_program;
+    asset.pool_state = ctx.accounts.pool_state.key();
+    asset.lst_sol_oracle = ctx.accounts.lst_sol_oracle.key();
+    asset.enabled = true;
+    asset.bump = ctx.bumps.asset;
+
+    // Pula musi zwracać poprawny kurs już przy rejestracji
+    let rate = lst::read_rate(&ctx.accounts.config, asset, &ctx.accounts.pool_state)?;
+
+    msg!(
+        "LST asset registered: {} ({:?}, pool: {}, rate: {} lamports)",
+        asset.mint,
+        rate_reader,
+        asset.pool_state,
+        rate
+    );
+    Ok(())
+}
+
+pub fn set_lst_asset_enabled_handler(ctx: Context<SetLstAssetEnabled>, enabled: bool) -> Result<()> {
+    ctx.accounts.asset.enabled = enabled;
+    msg!("LST asset {} enabled: {}", ctx.accounts.asset.mint, enabled);
+    Ok(())
+}
+
+pub fn add_payout_token_handler(ctx: Context<AddPayoutToken>, quote: PayoutQuote) -> Result<()> {
+    let payout_token = &mut ctx.accounts.payout_token;
+    payout_token.mint = ctx.accounts.mint.key();
+    payout_token.quote = quote;
+    payout_token.enabled = true;
+    payout_token.bump = ctx.bumps.payout_token;
+
+    msg!("Payout token added: {} ({:?})", payout_token.mint, quote);
+    Ok(())
+}
+
+pub fn set_payout_token_enabled_handler(
+    ctx: Context<SetPayoutTokenEnabled>,
+    enabled: bool,
+) -> Result<()> {
+    ctx.accounts.payout_token.enabled = enabled;
+    msg!("Payout token {} enabled: {}", ctx.accounts.payout_token.mint, enabled);
+    Ok(())
+}
+
+pub fn update_params_handler(ctx: Context<UpdateProgramParams>, params: ProgramParams) -> Result<()> {
+    require!(params.fee_rate <= MAX_PROTOCOL_FEE_BPS, ErrorCode::InvalidFeeRate);
+    require!(
+        params.management_fee_bps <= MAX_MANAGEMENT_FEE_BPS,
+        ErrorCode::InvalidManagementFee
+    );
+    require!(
+        !params.fees_enabled || ctx.accounts.config.fee_account != Pubkey::default(),
+        ErrorCode::InvalidFeeAccount
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.fees_enabled = params.fees_enabled;
+    config.fee_rate = params.fee_rate;
+    config.management_fee_bps = params.management_fee_bps;
+
+    msg!(
+        "Protocol fee {} ({} bps, management {} bps/year to {})",
+        if params.fees_enabled { "enabled" } else { "disabled" },
+        params.fee_rate,
+        params.management_fee_bps,
+        config.fee_account
+    );
+    Ok(())
+}
+
+pub fn initialize_fee_tiers_handler(ctx: Context<InitializeFeeTiers>) -> Result<()> {
+    let fee_tiers = &mut ctx.accounts.fee_tiers;
+    fee_tiers.tiers = Vec::new();
+    fee_tiers.bump = ctx.bumps.fee_tiers;
+    msg!("Fee tiers initialized (flat fee rate until tiers are set)");
+    Ok(())
+}
+
+pub fn set_fee_tiers_handler(ctx: Context<SetFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+    require!(tiers.len() <= MAX_FEE_TIERS, ErrorCode::InvalidFeeTiers);
+    if let Some(first) = tiers.first() {
+        require!(first.min_value == 0, ErrorCode::InvalidFeeTiers);
+    }
+    require!(
+        tiers.iter().all(|tier| tier.fee_bps <= MAX_PROTOCOL_FEE_BPS),
+        ErrorCode::InvalidFeeTiers
+    );
+    require!(
+        tiers.windows(2).all(|pair| pair[0].min_value < pair[1].min_value),
+        ErrorCode::InvalidFeeTiers
+    );
+
+    ctx.accounts.fee_tiers.tiers = tiers;
+    msg!("Fee tiers set: {:?}", ctx.accounts.fee_tiers.tiers);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// Plik implementujący listę beneficjentów wypłaty dywidendy
+//
+// Główne funkcje:
+// - Ustawia odbiorców wypłaty pozycji (np. zespół, fundacja, portfel prywatny)
+// - Każdy beneficjent otrzymuje część tokenu wypłaty wg swoich bps
+// - Pusta lista przywraca wypłatę w całości do właściciela
+// - Tylko dla wypłat w tokenie (PayoutMode::Usdc) - wypłaty SOL (liquid
+//   unstake, bilety) trafiają w całości do właściciela, więc lista przy
+//   trybach Sol / DelayedSol jest odrzucana (tu i w set_schedule)
+//
+// Struktury:
+// - SetBeneficiaries: Konta wymagane do zmiany listy
+//   * user_stake: Konto stakingowe użytkownika (mutable)
+//   * user: Podpisujący użytkownik (właściciel)
+//
+// Walidacja listy:
+// - Maksymalnie MAX_BENEFICIARIES odbiorców
+// - Udział każdego odbiorcy > 0, suma udziałów = MAX_BPS
+// - Portfele odbiorców bez powtórzeń
+//
+// Realizacja podziału: patrz claim::pay_recipients
+
+use anchor_lang::prelude::*;
+use crate::{
+    state::{Beneficiary, PayoutMode, UserStake},
+    utils::constants::{MAX_BENEFICIARIES, MAX_BPS},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct SetBeneficiaries<'info> {
+    #[account(mut, has_one = user)]
+    pub user_stake: Account<'info, UserStake>,
+    pub user: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetBeneficiaries>, beneficiaries: Vec<Beneficiary>) -> Result<()> {
+    validate_beneficiaries(&beneficiaries)?;
+    require!(
+        beneficiaries.is_empty() || ctx.accounts.user_stake.payout_mode == PayoutMode::Usdc,
+        ErrorCode::BeneficiariesRequireTokenPayout
+    );
+
+    msg!("Beneficiaries set: {}", beneficiaries.len());
+    ctx.accounts.user_stake.beneficiaries = beneficiaries;
+    Ok(())
+}
+
+fn validate_beneficiaries(beneficiaries: &[Beneficiary]) -> Result<()> {
+    if beneficiaries.is_empty() {
+        return Ok(());
+    }
+    require!(beneficiaries.len() <= MAX_BENEFICIARIES, ErrorCode::InvalidBeneficiaries);
+
+    let mut total_bps: u32 = 0;
+    for (i, beneficiary) in beneficiaries.iter().enumerate() {
+        require!(beneficiary.bps > 0, ErrorCode::InvalidBeneficiaries);
+        require!(
+            beneficiaries[..i].iter().all(|other| other.wallet != beneficiary.wallet),
+            ErrorCode::InvalidBeneficiaries
+        );
+        total_bps += beneficiary.bps as u32;
+    }
+    require!(total_bps == MAX_BPS as u32, ErrorCode::InvalidBeneficiaries);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn beneficiary(seed: u8, bps: u16) -> Beneficiary {
+        Beneficiary { wallet: Pubkey::new_from_array([seed; 32]), bps }
+    }
+
+    #[test]
+    fn empty_list_restores_owner_payout() {
+        assert!(validate_beneficiaries(&[]).is_ok());
+    }
+
+    #[test]
+    fn shares_must_sum_to_full_payout() {
+        assert!(validate_beneficiaries(&[beneficiary(1, 10_000)]).is_ok());
+        assert!(validate_beneficiaries(&[beneficiary(1, 2_500), beneficiary(2, 7_500)]).is_ok());
+        assert!(validate_beneficiaries(&[beneficiary(1, 9_999)]).is_err());
+        assert!(validate_beneficiaries(&[beneficiary(1, 5_000), beneficiary(2, 5_001)]).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_shares_and_duplicate_wallets() {
+        assert!(validate_beneficiaries(&[beneficiary(1, 0), beneficiary(2, 10_000)]).is_err());
+        assert!(validate_beneficiaries(&[beneficiary(1, 5_000), beneficiary(1, 5_000)]).is_err());
+    }
+
+    #[test]
+    fn rejects_more_than_max_beneficiaries() {
+        let bps = (MAX_BPS as usize / MAX_BENEFICIARIES) as u16;
+        let mut list: Vec<Beneficiary> = (0..MAX_BENEFICIARIES as u8)
+            .map(|seed| beneficiary(seed, bps))
+            .collect();
+        list[0].bps += MAX_BPS - bps * MAX_BENEFICIARIES as u16;
+        assert!(validate_beneficiaries(&list).is_ok());
+
+        list[0].bps -= 1;
+        list.push(beneficiary(MAX_BENEFICIARIES as u8, 1));
+        assert!(validate_beneficiaries(&list).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 // Plik obsługujący proces wypłat dywidend
 //
 // Główne funkcjonalności:
-// 1. Wypłata dywidend z mSOL w dwóch trybach:
+// 1. Wypłata dywidend z LST w dwóch trybach:
 //    - Auto: automatyczna wypłata zgodna z harmonogramem
 //    - Manual: ręczna wypłata pomijająca ograniczenia
 //
@@ -9,6 +9,8 @@
 //    - Sprawdza czy wypłata jest możliwa
 //    - Weryfikuje warunki harmonogramu (dla trybu auto)
 //    - Sprawdza minimalne kwoty wypłat
+//    - Wymaga trybu wypłaty Usdc (tryb Sol: patrz claim_sol.rs)
+//    - Token wypłaty pozycji (payout_mint) musi być na liście PayoutToken
 //
 // 3. Bezpieczeństwo:
 //    - Wymaga podpisu użytkownika
@@ -18,51 +20,141 @@
 // Struktury:
 // - ClaimDividend: Konta wymagane do wypłaty dywidendy
 // - ClaimMode: Enum określający tryb wypłaty (Auto/Manual)
+// - DividendClaimed: Event wypłaty (w tym zastosowany próg opłaty)
+//
+// remaining_accounts:
+// - najpierw konta tokenu wypłaty beneficjentów (w kolejności listy pozycji,
+//   brak gdy lista jest pusta), potem konta trasy Jupitera
 //
 // Proces wypłaty:
-// 1. Obliczenie aktualnej wartości dywidendy
+// 1. Obliczenie aktualnej wartości dywidendy (z LST faktycznie w skarbcu,
+//    kurs z puli zarejestrowanej dla aktywa pozycji)
 // 2. Walidacja zgodnie z trybem
-// 3. Wykonanie wypłaty przez integrację z Marinade
-// 4. Aktualizacja stanu użytkownika
+// 3. Realizacja dywidendy:
+//    - wypłacana część = dywidenda * payout_bps pozycji (zaokrąglenie w dół),
+//      reszta zostaje w skarbcu jako kapitał (total_compounded)
+//    - wypłacana część (lamporty) / aktualny kurs = LST zysku (zaokrąglenie w dół)
+//    - spalenie udziałów odpowiadających LST zysku (zaokrąglenie w górę)
+//    - wypłacana część zaokrąglona do 0 LST: bez swapu i opłaty protokołu,
+//      cała dywidenda kapitalizowana, harmonogram przesuwany jak po wypłacie
+//    - opłata protokołu (gdy fees_enabled) pobierana z LST zysku na konto LST
+//      właściciela opłat (fee_account), zapisywana w pozycji (total_fees)
+//      i w ProgramConfig (total_fees_collected); stawka z progu FeeTiers wg
+//      aktualnej wartości pozycji (pusta tabela = stała stawka fee_rate)
+//    - swap LST zysku -> token wypłaty przez Jupiter (podłoga z wyroczni Pyth:
+//      SOL/USD * LST/SOL dla stablecoinów, LST/SOL dla wSOL)
+//    - przelew tokenu wypłaty z konta skarbca do użytkownika lub podział
+//      między beneficjentów pozycji wg bps (reszta z zaokrągleń do pierwszego)
+// 4. Aktualizacja stanu użytkownika (kurs bazowy = aktualny)
 // 5. Aktualizacja harmonogramu (dla trybu auto)
+//
+// Wartość kapitału w SOL pozostaje nienaruszona - pozostałe LST po
+// aktualnym kursie są warte tyle, co przed wypłatą po kursie bazowym.
 
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::Token;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use crate::{
-    state::{UserStake, ProgramConfig},
-    utils::{marinade, math, schedule::ScheduleCalculator},
+    state::{Beneficiary, FeeTiers, LstAsset, UserStake, PayoutMode, PayoutToken, PauseTarget, ProgramConfig, Vault},
+    utils::{
+        constants::LAMPORTS_PER_SOL,
+        jupiter::{self, JUPITER_PROGRAM_ID},
+        lst,
+        math,
+        schedule::ScheduleCalculator
+    },
+    instructions::{management_fee::charge_management_fee, swap::oracle_min_out},
     errors::ErrorCode
 };
 
 
 #[derive(Accounts)]
 pub struct ClaimDividend<'info> {
-    #[account(mut, has_one = user)]
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
     pub user_stake: Account<'info, UserStake>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    #[account(mut, seeds = [b"escrow"], bump)]
-    pub escrow_account: AccountInfo<'info>,
-    
-    #[account(seeds = [b"config"], bump = config.bump)]
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused,
+        constraint = !config.is_paused(PauseTarget::Swaps) @ ErrorCode::OperationPaused
+    )]
     pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    /// Token wypłaty wybrany przez użytkownika (lista dozwolonych)
+    #[account(
+        seeds = [PayoutToken::SEED, user_stake.payout_mint.as_ref()],
+        bump = payout_token.bump,
+        constraint = payout_token.enabled @ ErrorCode::PayoutTokenDisabled
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+
+    /// Konto tokenu wypłaty skarbca - cel swapu Jupitera
+    #[account(
+        mut,
+        token::mint = payout_token.mint,
+        token::authority = vault
+    )]
+    pub vault_payout: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = payout_token.mint,
+        token::authority = user
+    )]
+    pub user_payout: Account<'info, TokenAccount>,
     
-    /// CHECK: Verified by Marinade program
-    pub marinade_program: AccountInfo<'info>,
-    
-    /// CHECK: Verified by Marinade program
-    pub msol_mint: AccountInfo<'info>,
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    #[account(address = asset.mint @ ErrorCode::InvalidLstMint)]
+    pub lst_mint: Account<'info, Mint>,
     
-    #[account(mut)]
-    /// CHECK: Verified by token program
-    pub usdc_mint: AccountInfo<'info>,
+    #[account(address = payout_token.mint @ ErrorCode::InvalidPayoutMint)]
+    pub payout_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = config.sol_usd_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub sol_usd_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = asset.lst_sol_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub lst_sol_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = JUPITER_PROGRAM_ID @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
     
     pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -71,24 +163,197 @@ pub enum ClaimMode {
     Manual,  // Ręczna wypłata (pomija harmonogram)
 }
 
-pub fn handler(ctx: Context<ClaimDividend>, mode: ClaimMode) -> Result<()> {
-    let user_stake = &mut ctx.accounts.user_stake;
+#[event]
+pub struct DividendClaimed {
+    pub user_stake: Pubkey,
+    /// Wypłacona część dywidendy w lamportach
+    pub dividend: u64,
+    /// Skapitalizowana część dywidendy w lamportach
+    pub compounded: u64,
+    /// Wartość pozycji przed wypłatą w lamportach (podstawa wyboru progu opłaty)
+    pub position_value: u64,
+    /// Opłata protokołu w lamportach
+    pub fee: u64,
+    pub fee_bps: u16,
+    /// Zastosowany próg z FeeTiers (None = stała stawka fee_rate)
+    pub fee_tier: Option<u8>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimDividend<'info>>,
+    mode: ClaimMode,
+    route_data: Vec<u8>,
+    slippage_bps: u16,
+) -> Result<()> {
     let clock = Clock::get()?;
     let current_timestamp = clock.unix_timestamp;
 
     // 1. Oblicz dywidendę
-    let current_msol_value = marinade::get_msol_rate(
-        &ctx.accounts.marinade_program,
-        &ctx.accounts.msol_mint
-    )?;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
     
-    let dividend = math::calculate_dividend(
-        user_stake.msol_amount,
-        user_stake.base_sol_value,
-        current_msol_value
+    // Opłata za zarządzanie rozliczana przed dywidendą
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_lst.amount,
+        current_rate,
+        current_timestamp
+    )?;
+
+    // LST użytkownika wyliczane z udziałów i salda skarbca (po opłacie)
+    let vault_balance = ctx.accounts.vault_lst.amount - management.lst;
+    let dividend = pending_dividend(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
     )?;
 
     // 2. Walidacja wypłaty
+    require!(
+        ctx.accounts.user_stake.payout_mode == PayoutMode::Usdc,
+        ErrorCode::PayoutModeMismatch
+    );
+    validate_claim(&ctx.accounts.user_stake, &mode, dividend, current_timestamp)?;
+
+    let beneficiaries = ctx.accounts.user_stake.beneficiaries.clone();
+    require!(
+        ctx.remaining_accounts.len() >= beneficiaries.len(),
+        ErrorCode::InvalidAccountConfig
+    );
+    let (beneficiary_accounts, route_accounts) = ctx.remaining_accounts.split_at(beneficiaries.len());
+
+    // 3. Realizacja dywidendy: tylko LST wypłacanej części zysku trafia do swapu
+    if dividend > 0 {
+        let (paid, compounded) = split_dividend(&ctx.accounts.user_stake, dividend)?;
+        let value = position_value(
+            &ctx.accounts.user_stake,
+            ctx.accounts.vault.total_shares,
+            vault_balance,
+            current_rate
+        )?;
+        let yield_lst = burn_yield_shares(
+            &mut ctx.accounts.user_stake,
+            &mut ctx.accounts.vault,
+            vault_balance,
+            paid,
+            current_rate
+        )?;
+        if yield_lst == 0 {
+            // Wypłacana część poniżej 1 LST - nic do sprzedaży, całość kapitalizowana
+            compound_unpaid_dividend(
+                &mut ctx.accounts.user_stake,
+                dividend,
+                value,
+                current_rate,
+                current_timestamp,
+                matches!(mode, ClaimMode::Auto)
+            )?;
+            return pay_protocol_fee(
+                &ctx.accounts.token_program,
+                &ctx.accounts.vault,
+                &ctx.accounts.vault_lst,
+                &ctx.accounts.fee_lst,
+                management.lst,
+            );
+        }
+        let fee = protocol_fee(&ctx.accounts.config, &ctx.accounts.fee_tiers, value, paid, yield_lst)?;
+        let swap_lst = yield_lst - fee.lst;
+
+        let min_amount_out = oracle_min_out(
+            &ctx.accounts.config,
+            &ctx.accounts.sol_usd_oracle,
+            &ctx.accounts.lst_sol_oracle,
+            ctx.accounts.payout_token.quote,
+            swap_lst,
+            ctx.accounts.lst_mint.decimals,
+            ctx.accounts.payout_mint.decimals,
+            slippage_bps,
+        )?;
+
+        let asset_mint = ctx.accounts.asset.mint;
+        let vault_bump = ctx.accounts.vault.bump;
+        let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[vault_bump]];
+        let swap = jupiter::swap_via_route(
+            &ctx.accounts.jupiter_program,
+            route_accounts,
+            route_data,
+            &mut ctx.accounts.vault_lst,
+            &mut ctx.accounts.vault_payout,
+            swap_lst,
+            min_amount_out,
+            &[vault_seeds],
+        )?;
+
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_lst,
+            &ctx.accounts.fee_lst,
+            fee.lst,
+        )?;
+        pay_recipients(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_payout,
+            &ctx.accounts.user_payout.to_account_info(),
+            &beneficiaries,
+            beneficiary_accounts,
+            swap.amount_out,
+        )?;
+
+        // 4. Aktualizacja stanu i harmonogramu (dla trybu auto)
+        record_dividend(
+            &mut ctx.accounts.user_stake,
+            paid,
+            compounded,
+            current_rate,
+            current_timestamp,
+            matches!(mode, ClaimMode::Auto)
+        )?;
+        record_fee(&mut ctx.accounts.user_stake, &mut ctx.accounts.config, fee.lamports)?;
+
+        msg!(
+            "Dividend paid: {} SOL as {} LST -> {} of {} (protocol fee: {}, compounded: {}, mode: {:?})", 
+            paid, 
+            swap.amount_in,
+            swap.amount_out,
+            ctx.accounts.payout_token.mint,
+            fee.lamports,
+            compounded,
+            mode
+        );
+        emit!(DividendClaimed {
+            user_stake: ctx.accounts.user_stake.key(),
+            dividend: paid,
+            compounded,
+            position_value: value,
+            fee: fee.lamports,
+            fee_bps: fee.bps,
+            fee_tier: fee.tier,
+        });
+    }
+
+    // Przelew opłaty za zarządzanie po swapie (swap liczy saldo LST sprzed przelewów)
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        management.lst,
+    )?;
+
+    Ok(())
+}
+
+/// Sprawdza warunki wypłaty zgodnie z trybem
+pub(crate) fn validate_claim(
+    user_stake: &UserStake,
+    mode: &ClaimMode,
+    dividend: u64,
+    current_timestamp: i64
+) -> Result<()> {
     match mode {
         ClaimMode::Auto => {
             require!(
@@ -113,39 +378,314 @@ pub fn handler(ctx: Context<ClaimDividend>, mode: ClaimMode) -> Result<()> {
         }
     }
 
-    // 3. Wypłata dywidendy
-    if dividend > 0 {
-        marinade::withdraw_dividend(
-            user_stake,
-            dividend,
-            &ctx.accounts.escrow_account,
-            &ctx.accounts.usdc_mint,
-            &ctx.accounts.token_program,
-            &ctx.accounts.system_program,
+    Ok(())
+}
+
+/// Niewypłacona dywidenda pozycji w lamportach (0 gdy kurs nie wzrósł)
+pub fn pending_dividend(
+    user_stake: &UserStake,
+    total_shares: u64,
+    vault_balance: u64,
+    current_rate: u64
+) -> Result<u64> {
+    if current_rate <= user_stake.base_sol_value {
+        return Ok(0);
+    }
+
+    let custodied_lst = math::shares_to_msol(user_stake.shares, total_shares, vault_balance)?;
+    math::calculate_dividend(custodied_lst, user_stake.base_sol_value, current_rate)
+}
+
+/// Dzieli dywidendę na część wypłacaną (payout_bps pozycji) i kapitalizowaną
+///
+/// Wypłacana część zaokrąglana w dół - reszta zostaje w skarbcu jako kapitał.
+pub(crate) fn split_dividend(user_stake: &UserStake, dividend: u64) -> Result<(u64, u64)> {
+    let paid = math::calculate_bps(dividend, user_stake.payout_bps)?;
+    let compounded = dividend
+        .checked_sub(paid)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok((paid, compounded))
+}
+
+/// Spala udziały odpowiadające LST zysku i zwraca ilość LST do sprzedaży
+///
+/// LST zaokrąglane w dół, udziały w górę - różnica zostaje w skarbcu.
+/// Dywidenda zaokrąglona do 0 LST zwraca 0 bez spalania udziałów
+/// (wywołujący kapitalizuje ją przez compound_unpaid_dividend).
+pub(crate) fn burn_yield_shares(
+    user_stake: &mut UserStake,
+    vault: &mut Vault,
+    vault_balance: u64,
+    dividend: u64,
+    current_rate: u64
+) -> Result<u64> {
+    let position_lst = math::shares_to_msol(user_stake.shares, vault.total_shares, vault_balance)?;
+    let yield_lst = math::dividend_to_msol(dividend, current_rate)?.min(position_lst);
+    if yield_lst == 0 {
+        return Ok(0);
+    }
+
+    let burn_shares = math::msol_to_shares_ceil(yield_lst, vault.total_shares, vault_balance)?
+        .min(user_stake.shares);
+    user_stake.shares = user_stake.shares
+        .checked_sub(burn_shares)
+        .ok_or(ErrorCode::InsufficientShares)?;
+    vault.total_shares = vault.total_shares
+        .checked_sub(burn_shares)
+        .ok_or(ErrorCode::InsufficientShares)?;
+
+    Ok(yield_lst)
+}
+
+/// Wartość pozycji w lamportach po aktualnym kursie (zaokrąglenie w dół)
+pub(crate) fn position_value(
+    user_stake: &UserStake,
+    total_shares: u64,
+    vault_balance: u64,
+    current_rate: u64
+) -> Result<u64> {
+    let position_lst = math::shares_to_msol(user_stake.shares, total_shares, vault_balance)?;
+    u64::try_from(
+        (position_lst as u128) * (current_rate as u128) / LAMPORTS_PER_SOL as u128
+    ).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Opłata protokołu pobrana z wypłacanej części dywidendy
+pub(crate) struct ProtocolFee {
+    /// Opłata w LST (przelew ze skarbca)
+    pub lst: u64,
+    /// Opłata w lamportach (statystyki)
+    pub lamports: u64,
+    /// Zastosowana stawka
+    pub bps: u16,
+    /// Indeks progu z FeeTiers (None = stała stawka fee_rate)
+    pub tier: Option<u8>,
+}
+
+/// Opłata protokołu od wypłacanej części dywidendy
+///
+/// Stawka z progu FeeTiers dla `position_value` (pusta tabela = fee_rate),
+/// zero gdy opłaty są wyłączone. Obie kwoty zaokrąglane w dół, opłata LST
+/// nie przekracza LST zysku.
+pub(crate) fn protocol_fee(
+    config: &ProgramConfig,
+    fee_tiers: &FeeTiers,
+    position_value: u64,
+    paid: u64,
+    yield_lst: u64
+) -> Result<ProtocolFee> {
+    if !config.fees_enabled {
+        return Ok(ProtocolFee { lst: 0, lamports: 0, bps: 0, tier: None });
+    }
+
+    let (tier, bps) = match fee_tiers.tier_for(position_value) {
+        Some((index, bps)) => (Some(index), bps),
+        None => (None, config.fee_rate),
+    };
+    Ok(ProtocolFee {
+        lst: math::calculate_bps(yield_lst, bps)?,
+        lamports: math::calculate_bps(paid, bps)?,
+        bps,
+        tier,
+    })
+}
+
+/// Przelew opłaty protokołu (LST) ze skarbca na konto właściciela opłat
+pub(crate) fn pay_protocol_fee<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, Vault>,
+    vault_lst: &Account<'info, TokenAccount>,
+    fee_lst: &Option<Account<'info, TokenAccount>>,
+    amount: u64
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let fee_lst = fee_lst.as_ref().ok_or(ErrorCode::InvalidFeeAccount)?;
+    pay_from_vault(token_program, vault, vault_lst, &fee_lst.to_account_info(), amount)
+}
+
+/// Zapisuje opłatę protokołu w statystykach pozycji i programu
+pub(crate) fn record_fee(user_stake: &mut UserStake, config: &mut ProgramConfig, fee: u64) -> Result<()> {
+    user_stake.total_fees = user_stake.total_fees
+        .checked_add(fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    config.total_fees_collected = config.total_fees_collected
+        .checked_add(fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Przelew tokenów z konta skarbca (podpis PDA Vault)
+pub(crate) fn pay_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, Vault>,
+    from: &Account<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    amount: u64
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, vault.mint.as_ref(), &[vault.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: from.to_account_info(),
+                to: to.clone(),
+                authority: vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        amount,
+    )
+}
+
+/// Wypłata ze skarbca do właściciela lub beneficjentów pozycji
+///
+/// Bez beneficjentów całość trafia na `owner_account`. Z beneficjentami
+/// `beneficiary_accounts` muszą odpowiadać liście (kolejność, mint konta skarbca,
+/// authority = wallet), kwota dzielona wg bps, reszta do pierwszego beneficjenta.
+pub(crate) fn pay_recipients<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, Vault>,
+    from: &Account<'info, TokenAccount>,
+    owner_account: &AccountInfo<'info>,
+    beneficiaries: &[Beneficiary],
+    beneficiary_accounts: &'info [AccountInfo<'info>],
+    amount: u64
+) -> Result<()> {
+    if beneficiaries.is_empty() {
+        return pay_from_vault(token_program, vault, from, owner_account, amount);
+    }
+    require!(
+        beneficiary_accounts.len() == beneficiaries.len(),
+        ErrorCode::InvalidBeneficiaryAccount
+    );
+
+    let shares_bps: Vec<u16> = beneficiaries.iter().map(|beneficiary| beneficiary.bps).collect();
+    let parts = math::split_by_bps(amount, &shares_bps)?;
+    for ((beneficiary, account_info), part) in beneficiaries.iter().zip(beneficiary_accounts).zip(parts) {
+        let account: Account<'info, TokenAccount> = Account::try_from(account_info)?;
+        require_keys_eq!(account.mint, from.mint, ErrorCode::InvalidBeneficiaryAccount);
+        require_keys_eq!(account.owner, beneficiary.wallet, ErrorCode::InvalidBeneficiaryAccount);
+        pay_from_vault(token_program, vault, from, account_info, part)?;
+    }
+
+    Ok(())
+}
+
+/// Rozlicza dywidendę, której wypłacana część zaokrągla się do 0 LST
+///
+/// Nic nie jest wypłacane - cała dywidenda zostaje w kapitale pozycji,
+/// a harmonogram przesuwa się jak po wypłacie.
+pub(crate) fn compound_unpaid_dividend(
+    user_stake: &mut Account<UserStake>,
+    dividend: u64,
+    position_value: u64,
+    current_rate: u64,
+    current_timestamp: i64,
+    advance_schedule: bool
+) -> Result<()> {
+    record_dividend(user_stake, 0, dividend, current_rate, current_timestamp, advance_schedule)?;
+
+    msg!("Dividend below 1 LST unit, compounded: {} lamports", dividend);
+    emit!(DividendClaimed {
+        user_stake: user_stake.key(),
+        dividend: 0,
+        compounded: dividend,
+        position_value,
+        fee: 0,
+        fee_bps: 0,
+        fee_tier: None,
+    });
+    Ok(())
+}
+
+/// Aktualizuje stan pozycji po wypłacie dywidendy
+///
+/// `paid` trafia do statystyk wypłat, `compounded` (niewypłacona reszta
+/// dywidendy) zostaje w kapitale pozycji - kurs bazowy obejmuje obie części.
+pub(crate) fn record_dividend(
+    user_stake: &mut UserStake,
+    paid: u64,
+    compounded: u64,
+    current_rate: u64,
+    current_timestamp: i64,
+    advance_schedule: bool
+) -> Result<()> {
+    user_stake.base_sol_value = current_rate;
+    user_stake.last_dividend = paid;
+    user_stake.total_dividends = user_stake.total_dividends
+        .checked_add(paid)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user_stake.total_compounded = user_stake.total_compounded
+        .checked_add(compounded)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    if advance_schedule {
+        user_stake.next_payout_date = ScheduleCalculator::calculate_next_payout(
+            user_stake.payout_schedule,
+            current_timestamp
         )?;
+    }
 
-        // 4. Aktualizacja stanu
-        user_stake.base_sol_value = current_msol_value;
-        user_stake.last_update = current_timestamp;
-        user_stake.last_dividend = dividend;
-        user_stake.total_dividends = user_stake.total_dividends
-            .checked_add(dividend)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        // 5. Aktualizacja harmonogramu dla trybu auto
-        if let ClaimMode::Auto = mode {
-            user_stake.next_payout_date = ScheduleCalculator::calculate_next_payout(
-                user_stake.payout_schedule,
-                current_timestamp
-            )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PayoutSchedule;
+
+    const RATE: u64 = 1_100_000_000;
+
+    fn vault(total_shares: u64) -> Vault {
+        Vault {
+            mint: Pubkey::default(),
+            token_account: Pubkey::default(),
+            total_shares,
+            bump: 0,
+            token_bump: 0,
         }
+    }
 
-        msg!(
-            "Dividend paid: {} SOL (mode: {:?})", 
-            dividend, 
-            mode
-        );
+    #[test]
+    fn yield_rounding_to_zero_lst_burns_nothing() {
+        let mut user_stake = UserStake {
+            shares: 1_000,
+            base_sol_value: 1_000_000_000,
+            ..Default::default()
+        };
+        let mut vault = vault(1_000);
+
+        // 1 lamport przy kursie 1.1 SOL = 0 LST
+        let yield_lst = burn_yield_shares(&mut user_stake, &mut vault, 1_000, 1, RATE).unwrap();
+        assert_eq!(yield_lst, 0);
+        assert_eq!(user_stake.shares, 1_000);
+        assert_eq!(vault.total_shares, 1_000);
     }
 
-    Ok(())
-}
\ No newline at end of file
+    #[test]
+    fn unpaid_dividend_is_compounded_and_advances_schedule() {
+        let now = 1_700_000_000;
+        let mut user_stake = UserStake {
+            shares: 1_000,
+            base_sol_value: 1_000_000_000,
+            payout_schedule: PayoutSchedule::Daily,
+            next_payout_date: now,
+            ..Default::default()
+        };
+
+        // Rozliczenie jak w compound_unpaid_dividend: nic wypłacone, całość w kapitale
+        record_dividend(&mut user_stake, 0, 100, RATE, now, true).unwrap();
+        assert_eq!(user_stake.base_sol_value, RATE);
+        assert_eq!(user_stake.last_dividend, 0);
+        assert_eq!(user_stake.total_dividends, 0);
+        assert_eq!(user_stake.total_compounded, 100);
+        assert_eq!(user_stake.next_payout_date, now + 86_400);
+    }
+}
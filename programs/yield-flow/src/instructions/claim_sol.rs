@@ -0,0 +1,269 @@
+// Plik obsługujący wypłatę dywidendy w natywnym SOL
+//
+// Główne funkcjonalności:
+// 1. Wypłata dywidendy dla pozycji w trybie PayoutMode::Sol
+//    - Tryby Auto/Manual jak w claim.rs (te same warunki walidacji)
+//
+// 2. Realizacja przez Marinade liquid unstake:
+//    - mSOL zysku = dywidenda / aktualny kurs (zaokrąglenie w dół)
+//    - spalenie udziałów odpowiadających mSOL zysku (zaokrąglenie w górę)
+//    - opłata protokołu (jak w claim.rs) pobierana w mSOL przed unstake
+//    - CPI liquid_unstake: mSOL ze skarbca (podpis PDA Vault) -> SOL
+//      bezpośrednio na konto właściciela
+//
+// 3. Limit opłaty:
+//    - Otrzymane SOL porównywane z wartością mSOL po kursie Marinade
+//    - Opłata powyżej max_unstake_fee_bps pozycji przerywa wypłatę
+//
+// Struktury:
+// - ClaimDividendSol: Konta wymagane do wypłaty (w tym konta puli
+//   płynności Marinade, jak w DepositSol)
+//
+// Bezpieczeństwo:
+// - Wymaga podpisu właściciela pozycji
+// - Tylko aktywo Marinade z rejestru (program i State z wpisu aktywa)
+// - Kwota SOL weryfikowana różnicą sald konta właściciela
+// - Tylko pozycje bez beneficjentów (SOL w całości do właściciela)
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    state::{FeeTiers, LstAsset, PauseTarget, PayoutMode, ProgramConfig, RateReader, UserStake, Vault},
+    utils::{
+        constants::{LAMPORTS_PER_SOL, MAX_BPS},
+        lst,
+        marinade::{self, LiquidUnstakeAccounts}
+    },
+    instructions::{
+        claim::{
+            burn_yield_shares, compound_unpaid_dividend, pay_protocol_fee, pending_dividend,
+            position_value, protocol_fee, record_dividend, record_fee, split_dividend,
+            validate_claim, ClaimMode, DividendClaimed
+        },
+        management_fee::charge_management_fee
+    },
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct ClaimDividendSol<'info> {
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    /// Właściciel pozycji - odbiorca SOL
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// Tylko aktywo Marinade (mSOL)
+    #[account(
+        seeds = [LstAsset::SEED, asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.rate_reader == RateReader::Marinade @ ErrorCode::UnsupportedLstAsset
+    )]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_msol: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Verified by address
+    #[account(address = asset.pool_program @ ErrorCode::InvalidMarinadeProgram)]
+    pub marinade_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by lst::read_rate
+    #[account(mut, address = asset.pool_state @ ErrorCode::InvalidMarinadeState)]
+    pub marinade_state: AccountInfo<'info>,
+
+    #[account(mut, address = asset.mint @ ErrorCode::InvalidMsolMint)]
+    pub msol_mint: Account<'info, Mint>,
+
+    // Marinade liquidity pool accounts
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub liq_pool_sol_leg: AccountInfo<'info>,
+
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub liq_pool_msol_leg: AccountInfo<'info>,
+
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub treasury_msol_account: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimDividendSol>, mode: ClaimMode) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    // 1. Oblicz dywidendę
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.marinade_state)?;
+
+    // Opłata za zarządzanie rozliczana przed dywidendą
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_msol.amount,
+        current_rate,
+        current_timestamp
+    )?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_msol,
+        &ctx.accounts.fee_lst,
+        management.lst,
+    )?;
+    let vault_balance = ctx.accounts.vault_msol.amount - management.lst;
+    let dividend = pending_dividend(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+
+    // 2. Walidacja wypłaty
+    require!(
+        ctx.accounts.user_stake.payout_mode == PayoutMode::Sol,
+        ErrorCode::PayoutModeMismatch
+    );
+    // SOL trafia w całości do właściciela - beneficjenci tylko przy wypłacie w tokenie
+    require!(
+        ctx.accounts.user_stake.beneficiaries.is_empty(),
+        ErrorCode::BeneficiariesRequireTokenPayout
+    );
+    validate_claim(&ctx.accounts.user_stake, &mode, dividend, current_timestamp)?;
+    if dividend == 0 {
+        return Ok(());
+    }
+
+    // 3. Liquid unstake mSOL wypłacanej części zysku prosto na konto właściciela
+    let (paid, compounded) = split_dividend(&ctx.accounts.user_stake, dividend)?;
+    let value = position_value(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+    let yield_msol = burn_yield_shares(
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        vault_balance,
+        paid,
+        current_rate
+    )?;
+    if yield_msol == 0 {
+        // Wypłacana część poniżej 1 mSOL - nic do unstake, całość kapitalizowana
+        return compound_unpaid_dividend(
+            &mut ctx.accounts.user_stake,
+            dividend,
+            value,
+            current_rate,
+            current_timestamp,
+            matches!(mode, ClaimMode::Auto)
+        );
+    }
+    let protocol = protocol_fee(&ctx.accounts.config, &ctx.accounts.fee_tiers, value, paid, yield_msol)?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_msol,
+        &ctx.accounts.fee_lst,
+        protocol.lst,
+    )?;
+    let yield_msol = yield_msol - protocol.lst;
+
+    let lamports_before = ctx.accounts.user.lamports();
+    let asset_mint = ctx.accounts.asset.mint;
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[ctx.accounts.vault.bump]];
+    marinade::liquid_unstake(
+        LiquidUnstakeAccounts {
+            marinade_program: &ctx.accounts.marinade_program,
+            state: &ctx.accounts.marinade_state,
+            msol_mint: &ctx.accounts.msol_mint.to_account_info(),
+            liq_pool_sol_leg: &ctx.accounts.liq_pool_sol_leg,
+            liq_pool_msol_leg: &ctx.accounts.liq_pool_msol_leg,
+            treasury_msol_account: &ctx.accounts.treasury_msol_account,
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+        },
+        &ctx.accounts.vault_msol.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        yield_msol,
+        &[vault_seeds],
+    )?;
+    let received = ctx.accounts.user.lamports().saturating_sub(lamports_before);
+
+    // 4. Limit opłaty: fee / wartość <= max_unstake_fee_bps / MAX_BPS
+    let expected = (yield_msol as u128)
+        .checked_mul(current_rate as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / LAMPORTS_PER_SOL as u128;
+    let fee = expected.saturating_sub(received as u128);
+    require!(
+        fee * (MAX_BPS as u128)
+            <= expected * ctx.accounts.user_stake.max_unstake_fee_bps as u128,
+        ErrorCode::UnstakeFeeTooHigh
+    );
+
+    // 5. Aktualizacja stanu i harmonogramu (dla trybu auto)
+    record_dividend(
+        &mut ctx.accounts.user_stake,
+        paid,
+        compounded,
+        current_rate,
+        current_timestamp,
+        matches!(mode, ClaimMode::Auto)
+    )?;
+    record_fee(&mut ctx.accounts.user_stake, &mut ctx.accounts.config, protocol.lamports)?;
+
+    msg!(
+        "Dividend paid: {} SOL as {} mSOL -> {} lamports (unstake fee: {}, protocol fee: {}, compounded: {}, mode: {:?})",
+        paid,
+        yield_msol,
+        received,
+        fee,
+        protocol.lamports,
+        compounded,
+        mode
+    );
+    emit!(DividendClaimed {
+        user_stake: ctx.accounts.user_stake.key(),
+        dividend: paid,
+        compounded,
+        position_value: value,
+        fee: protocol.lamports,
+        fee_bps: protocol.bps,
+        fee_tier: protocol.tier,
+    });
+    Ok(())
+}
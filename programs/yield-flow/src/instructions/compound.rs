@@ -0,0 +1,270 @@
+// Plik implementujący kapitalizację zysku (auto-compound) i jej projekcję
+//
+// Główne funkcjonalności:
+// 1. Kapitalizacja dywidendy (compound_dividend)
+//    - Dostępna dla pozycji z włączonym auto_compound, niezależnie od
+//      harmonogramu wypłat (PayoutSchedule)
+//    - Wywoływana wyłącznie przez właściciela - kapitalizuje cały zysk, także
+//      część wypłacaną wg payout_bps, więc keeper nie może jej wymusić
+//    - LST zysku zostaje w skarbcu - spalane są tylko udziały opłat
+//    - Wcześniej rozliczana opłata za zarządzanie (przelew LST opłaty)
+//    - Opłata protokołu od kapitalizowanej dywidendy jak przy wypłacie
+//      (protocol_fee, stawka z FeeTiers) - kapitalizacja nie omija opłaty
+//    - Kurs bazowy pozycji przesuwany na aktualny - zysk staje się kapitałem
+//
+// 2. Projekcja kapitalizacji (project_compound - widok)
+//    - Kapitał = aktualna wartość pozycji w lamportach (LST * kurs puli)
+//    - Saldo po każdym z N okresów liczone przez calculate_compound_interest
+//      dla stopy rate_per_period_bps podanej przez klienta (project_balances)
+//    - Wynik zwracany jako dane zwrotne instrukcji (CompoundProjection)
+//
+// Struktury:
+// - CompoundDividend: Konta wymagane do kapitalizacji
+// - ProjectCompound: Konta wymagane do projekcji (tylko odczyt)
+// - CompoundProjection: Wynik projekcji
+// - DividendCompounded: Event kapitalizacji
+//
+// Bezpieczeństwo:
+// - Kapitalizacja przenosi tylko LST opłat (za zarządzanie i protokołu) -
+//   poza tym zmienia wyłącznie kurs bazowy pozycji
+// - Tylko pozycje, których właściciel włączył auto_compound, i tylko jego podpisem
+// - Liczba okresów projekcji ograniczona (MAX_PROJECTION_PERIODS)
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::{
+    state::{FeeTiers, LstAsset, PauseTarget, ProgramConfig, UserStake, Vault},
+    utils::{constants::MAX_PROJECTION_PERIODS, lst, math},
+    instructions::{
+        claim::{pay_protocol_fee, pending_dividend, position_value, protocol_fee, record_fee},
+        management_fee::{burn_fee_shares, charge_management_fee}
+    },
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct CompoundDividend<'info> {
+    /// Właściciel pozycji
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user-stake", user_stake.user.as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump,
+        has_one = user,
+        constraint = user_stake.auto_compound @ ErrorCode::AutoCompoundDisabled
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ProjectCompound<'info> {
+    #[account(
+        seeds = [b"user-stake", user_stake.user.as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CompoundProjection {
+    /// Aktualna wartość pozycji w lamportach
+    pub principal: u64,
+    /// Saldo w lamportach po każdym okresie (balances[i] = po i + 1 okresach)
+    pub balances: Vec<u64>,
+    /// Łączne odsetki po wszystkich okresach
+    pub total_interest: u64,
+}
+
+#[event]
+pub struct DividendCompounded {
+    pub user_stake: Pubkey,
+    pub dividend: u64,
+    /// Opłata protokołu w lamportach (pobrana z kapitalizowanej dywidendy)
+    pub fee: u64,
+    pub base_sol_value: u64,
+}
+
+pub fn compound_handler(ctx: Context<CompoundDividend>) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+
+    // Opłata za zarządzanie rozliczana przed zmianą kursu bazowego
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_lst.amount,
+        current_rate,
+        current_timestamp
+    )?;
+    let vault_balance = ctx.accounts.vault_lst.amount - management.lst;
+    let dividend = pending_dividend(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+    require!(dividend > 0, ErrorCode::NoDividendToClaim);
+
+    // Opłata protokołu od kapitalizowanej dywidendy (jak przy wypłacie)
+    let value = position_value(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+    let yield_lst = math::dividend_to_msol(dividend, current_rate)?;
+    let fee = protocol_fee(&ctx.accounts.config, &ctx.accounts.fee_tiers, value, dividend, yield_lst)?;
+    if fee.lst > 0 {
+        burn_fee_shares(&mut ctx.accounts.user_stake, &mut ctx.accounts.vault, vault_balance, fee.lst)?;
+    }
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        management.lst + fee.lst,
+    )?;
+
+    // Reszta zysku zostaje w skarbcu - kapitał rośnie o dywidendę po opłacie
+    let compounded = dividend - fee.lamports;
+    let user_stake = &mut ctx.accounts.user_stake;
+    user_stake.base_sol_value = current_rate;
+    user_stake.total_compounded = user_stake.total_compounded
+        .checked_add(compounded)
+        .ok_or(ErrorCode::MathOverflow)?;
+    record_fee(user_stake, &mut ctx.accounts.config, fee.lamports)?;
+
+    emit!(DividendCompounded {
+        user_stake: user_stake.key(),
+        dividend: compounded,
+        fee: fee.lamports,
+        base_sol_value: current_rate,
+    });
+    Ok(())
+}
+
+pub fn projection_handler(
+    ctx: Context<ProjectCompound>,
+    periods: u64,
+    rate_per_period_bps: u64,
+) -> Result<CompoundProjection> {
+    require!(
+        periods > 0 && periods <= MAX_PROJECTION_PERIODS,
+        ErrorCode::InvalidProjection
+    );
+
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+    let principal = position_value(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        ctx.accounts.vault_lst.amount,
+        current_rate
+    )?;
+
+    let balances = project_balances(principal, periods, rate_per_period_bps)?;
+    let total_interest = balances.last().map_or(0, |balance| balance - principal);
+
+    Ok(CompoundProjection {
+        principal,
+        balances,
+        total_interest,
+    })
+}
+
+/// Saldo po każdym z `periods` okresów przy stopie `rate_per_period_bps`
+fn project_balances(principal: u64, periods: u64, rate_per_period_bps: u64) -> Result<Vec<u64>> {
+    // Okres po okresie - zaokrąglenie w dół w każdym okresie, jak w calculate_compound_interest
+    let mut balances = Vec::with_capacity(periods as usize);
+    let mut balance = principal;
+    for _ in 0..periods {
+        balance = balance
+            .checked_add(math::calculate_compound_interest(balance, rate_per_period_bps, 1)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        balances.push(balance);
+    }
+
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projection_matches_compound_interest() {
+        let balances = project_balances(1_000_000_000, 12, 50).unwrap();
+        assert_eq!(balances.len(), 12);
+        assert!(balances.windows(2).all(|pair| pair[0] < pair[1]));
+        // Ostatnie saldo = kapitał + procent składany za wszystkie okresy
+        assert_eq!(
+            balances[11],
+            1_000_000_000 + math::calculate_compound_interest(1_000_000_000, 50, 12).unwrap()
+        );
+    }
+
+    #[test]
+    fn projection_with_zero_rate_keeps_principal() {
+        assert_eq!(project_balances(5_000, 3, 0).unwrap(), vec![5_000; 3]);
+    }
+
+    #[test]
+    fn projection_rounds_down_each_period() {
+        // 1% od 150 = 1.5 -> 1, od 151 = 1.51 -> 1
+        assert_eq!(project_balances(150, 2, 100).unwrap(), vec![151, 152]);
+    }
+
+    #[test]
+    fn projection_overflow_is_an_error() {
+        assert!(project_balances(u64::MAX / 2, 2, 10_000).is_err());
+    }
+}
@@ -0,0 +1,765 @@
+// Plik implementujący bezpośredni (permissionless) crank auto-wypłat
+//
+// Główne funkcjonalności:
+// 1. Auto-wypłata wywoływana przez dowolnego keepera
+//    - Nie wymaga podpisu właściciela pozycji
+//    - Dozwolona tylko gdy ScheduleCalculator::should_payout == true
+//
+// 2. Podział dywidendy
+//    - LST zysku sprzedawane przez Jupiter (trasa od keepera, podłoga z
+//      wyroczni minus oracle_max_slippage_bps z ProgramConfig)
+//    - Nagroda keepera: keeper_bounty_bps z otrzymanego tokenu wypłaty
+//    - Reszta trafia na konto tokenu wypłaty właściciela (payout_mint pozycji)
+//      lub jest dzielona między beneficjentów pozycji (konta beneficjentów
+//      na początku remaining_accounts, potem konta trasy Jupitera)
+//
+// 3. Crank wsadowy (batch)
+//    - remaining_accounts: najpierw `position_count` grup
+//      [user_stake, owner_payout, konta beneficjentów...], potem konta trasy
+//      Jupitera (liczba kont beneficjentów = długość listy pozycji)
+//    - Wszystkie pozycje w paczce należą do jednego aktywa LST (skarbca)
+//      i mają ten sam token wypłaty
+//    - Pozycje niegotowe (harmonogram, minimum), w trybie wypłaty Sol lub
+//      z innym tokenem wypłaty są pomijane, nie przerywają transakcji
+//    - Dywidenda sprawdzana ponownie po opłacie za zarządzanie - pozycja,
+//      której zysk spadł poniżej minimum (lub do 0 LST), jest pomijana
+//      z pobraną opłatą
+//    - LST zysku wszystkich wypłacanych pozycji sprzedawane jednym swapem,
+//      token wypłaty dzielony proporcjonalnie do LST (reszta z zaokrągleń w skarbcu)
+//    - Dla każdej pozycji emitowany jest event CrankClaimResult
+//
+// Struktury:
+// - CrankClaim: Konta wymagane do cranka
+// - BatchCrankClaim: Konta wspólne dla cranka wsadowego
+// - CrankClaimStatus / CrankClaimResult: Wynik dla pojedynczej pozycji
+// - BatchSettlement: Rozliczenie pozycji paczki (settle_batch_position -
+//   czysta funkcja, z której keeper liczy kwotę swapu paczki)
+//
+// Bezpieczeństwo:
+// - Konto tokenu wypłaty właściciela weryfikowane (mint + authority = user_stake.user)
+// - Keeper nie ma wpływu na kwotę ani odbiorcę wypłaty - trasa Jupitera
+//   weryfikowana różnicą sald i podłogą z wyroczni
+// - Harmonogram przesuwany po każdej wypłacie (brak podwójnych wypłat)
+// - Opłata protokołu (gdy włączona) pobierana z LST zysku przed swapem,
+//   tylko na konto LST właściciela opłat z ProgramConfig
+// - Opłata za zarządzanie rozliczana przed dywidendą (tylko wypłacane pozycje)
+// - Konta z remaining_accounts weryfikowane (właściciel, PDA, mint, authority)
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    state::{FeeTiers, LstAsset, PauseTarget, PayoutMode, PayoutSchedule, PayoutToken, ProgramConfig, UserStake, Vault},
+    utils::{jupiter::{self, JUPITER_PROGRAM_ID}, lst, math, schedule::ScheduleCalculator},
+    instructions::{
+        claim::{
+            burn_yield_shares, compound_unpaid_dividend, pay_from_vault, pay_protocol_fee,
+            pay_recipients, pending_dividend, position_value, protocol_fee, record_dividend,
+            record_fee, split_dividend, DividendClaimed
+        },
+        management_fee::charge_management_fee,
+        swap::oracle_min_out
+    },
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct CrankClaim<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user-stake", user_stake.user.as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused,
+        constraint = !config.is_paused(PauseTarget::Swaps) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    /// Token wypłaty wybrany przez właściciela pozycji
+    #[account(
+        seeds = [PayoutToken::SEED, user_stake.payout_mint.as_ref()],
+        bump = payout_token.bump,
+        constraint = payout_token.enabled @ ErrorCode::PayoutTokenDisabled
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+
+    /// Konto tokenu wypłaty właściciela pozycji
+    #[account(
+        mut,
+        token::mint = payout_token.mint,
+        token::authority = user_stake.user
+    )]
+    pub owner_payout: Account<'info, TokenAccount>,
+
+    /// Konto tokenu wypłaty keepera (nagroda)
+    #[account(mut, token::mint = payout_token.mint)]
+    pub keeper_payout: Account<'info, TokenAccount>,
+
+    /// Konto tokenu wypłaty skarbca - cel swapu Jupitera
+    #[account(
+        mut,
+        token::mint = payout_token.mint,
+        token::authority = vault
+    )]
+    pub vault_payout: Account<'info, TokenAccount>,
+
+    #[account(address = asset.mint @ ErrorCode::InvalidLstMint)]
+    pub lst_mint: Account<'info, Mint>,
+
+    #[account(address = payout_token.mint @ ErrorCode::InvalidPayoutMint)]
+    pub payout_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = config.sol_usd_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub sol_usd_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = asset.lst_sol_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub lst_sol_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = JUPITER_PROGRAM_ID @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CrankClaim<'info>>,
+    route_data: Vec<u8>,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+
+    // Opłata za zarządzanie rozliczana przed dywidendą
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_lst.amount,
+        current_rate,
+        current_timestamp
+    )?;
+    let vault_balance = ctx.accounts.vault_lst.amount - management.lst;
+    let dividend = pending_dividend(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+
+    // 1. Walidacja - tylko pozycje gotowe do auto-wypłaty przez swap
+    require!(
+        ctx.accounts.user_stake.payout_mode == PayoutMode::Usdc,
+        ErrorCode::PayoutModeMismatch
+    );
+    require!(
+        ScheduleCalculator::should_payout(&ctx.accounts.user_stake, dividend, current_timestamp)?,
+        ErrorCode::PayoutNotDue
+    );
+    require!(dividend > 0, ErrorCode::NoDividendToClaim);
+
+    let beneficiaries = ctx.accounts.user_stake.beneficiaries.clone();
+    require!(
+        ctx.remaining_accounts.len() >= beneficiaries.len(),
+        ErrorCode::InvalidAccountConfig
+    );
+    let (beneficiary_accounts, route_accounts) = ctx.remaining_accounts.split_at(beneficiaries.len());
+
+    // 2. Sprzedaż LST wypłacanej części zysku (reszta kapitalizowana)
+    let (paid, compounded) = split_dividend(&ctx.accounts.user_stake, dividend)?;
+    let value = position_value(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+    let yield_lst = burn_yield_shares(
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        vault_balance,
+        paid,
+        current_rate
+    )?;
+    if yield_lst == 0 {
+        // Wypłacana część poniżej 1 LST - nic do sprzedaży, całość kapitalizowana
+        compound_unpaid_dividend(
+            &mut ctx.accounts.user_stake,
+            dividend,
+            value,
+            current_rate,
+            current_timestamp,
+            true
+        )?;
+        return pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_lst,
+            &ctx.accounts.fee_lst,
+            management.lst,
+        );
+    }
+    let fee = protocol_fee(&ctx.accounts.config, &ctx.accounts.fee_tiers, value, paid, yield_lst)?;
+    let swap_lst = yield_lst - fee.lst;
+    let min_amount_out = oracle_min_out(
+        &ctx.accounts.config,
+        &ctx.accounts.sol_usd_oracle,
+        &ctx.accounts.lst_sol_oracle,
+        ctx.accounts.payout_token.quote,
+        swap_lst,
+        ctx.accounts.lst_mint.decimals,
+        ctx.accounts.payout_mint.decimals,
+        ctx.accounts.config.oracle_max_slippage_bps,
+    )?;
+
+    let asset_mint = ctx.accounts.asset.mint;
+    let vault_bump = ctx.accounts.vault.bump;
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[vault_bump]];
+    let swap = jupiter::swap_via_route(
+        &ctx.accounts.jupiter_program,
+        route_accounts,
+        route_data,
+        &mut ctx.accounts.vault_lst,
+        &mut ctx.accounts.vault_payout,
+        swap_lst,
+        min_amount_out,
+        &[vault_seeds],
+    )?;
+    // Opłata protokołu i opłata za zarządzanie jednym przelewem
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        fee.lst + management.lst,
+    )?;
+
+    // 3. Podział: nagroda keepera + wypłata właściciela
+    let (bounty, owner_amount) = math::split_keeper_bounty(swap.amount_out, ctx.accounts.config.keeper_bounty_bps)?;
+
+    pay_recipients(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_payout,
+        &ctx.accounts.owner_payout.to_account_info(),
+        &beneficiaries,
+        beneficiary_accounts,
+        owner_amount,
+    )?;
+    pay_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_payout,
+        &ctx.accounts.keeper_payout.to_account_info(),
+        bounty,
+    )?;
+
+    // 4. Aktualizacja stanu i harmonogramu
+    record_dividend(&mut ctx.accounts.user_stake, paid, compounded, current_rate, current_timestamp, true)?;
+    record_fee(&mut ctx.accounts.user_stake, &mut ctx.accounts.config, fee.lamports)?;
+
+    msg!(
+        "Crank paid dividend: {} lamports -> {} of {} (owner: {}, keeper bounty: {}, protocol fee: {})",
+        paid,
+        swap.amount_out,
+        ctx.accounts.payout_token.mint,
+        owner_amount,
+        bounty,
+        fee.lamports
+    );
+    emit!(DividendClaimed {
+        user_stake: ctx.accounts.user_stake.key(),
+        dividend: paid,
+        compounded,
+        position_value: value,
+        fee: fee.lamports,
+        fee_bps: fee.bps,
+        fee_tier: fee.tier,
+    });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct BatchCrankClaim<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused,
+        constraint = !config.is_paused(PauseTarget::Swaps) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    /// Token wypłaty wspólny dla wszystkich pozycji w paczce
+    #[account(
+        seeds = [PayoutToken::SEED, payout_token.mint.as_ref()],
+        bump = payout_token.bump,
+        constraint = payout_token.enabled @ ErrorCode::PayoutTokenDisabled
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+
+    /// Konto tokenu wypłaty keepera (nagroda)
+    #[account(mut, token::mint = payout_token.mint)]
+    pub keeper_payout: Account<'info, TokenAccount>,
+
+    /// Konto tokenu wypłaty skarbca - cel swapu Jupitera
+    #[account(
+        mut,
+        token::mint = payout_token.mint,
+        token::authority = vault
+    )]
+    pub vault_payout: Account<'info, TokenAccount>,
+
+    #[account(address = asset.mint @ ErrorCode::InvalidLstMint)]
+    pub lst_mint: Account<'info, Mint>,
+
+    #[account(address = payout_token.mint @ ErrorCode::InvalidPayoutMint)]
+    pub payout_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = config.sol_usd_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub sol_usd_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = asset.lst_sol_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub lst_sol_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = JUPITER_PROGRAM_ID @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum CrankClaimStatus {
+    Paid,
+    AutoClaimDisabled,
+    NotDue,
+    BelowMinimum,
+    UnsupportedPayoutMode,
+    PayoutMintMismatch,
+}
+
+#[event]
+pub struct CrankClaimResult {
+    pub user_stake: Pubkey,
+    pub status: CrankClaimStatus,
+    /// Dywidenda w lamportach (dla Paid - wypłacona część, bez kapitalizowanej)
+    pub dividend: u64,
+    /// Nagroda keepera w tokenie wypłaty
+    pub bounty: u64,
+    /// Stawka opłaty protokołu (0 dla pominiętych pozycji)
+    pub fee_bps: u16,
+    /// Zastosowany próg z FeeTiers (None = stała stawka fee_rate lub pozycja pominięta)
+    pub fee_tier: Option<u8>,
+}
+
+/// Rozliczenie jednej wypłacanej pozycji paczki
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BatchSettlement {
+    /// Paid lub powód pominięcia po opłacie za zarządzanie
+    pub status: CrankClaimStatus,
+    /// LST opłaty za zarządzanie (przelew na konto opłat)
+    pub management_lst: u64,
+    /// Wypłacana część dywidendy w lamportach (po opłacie za zarządzanie)
+    pub paid_dividend: u64,
+    /// LST zysku do wspólnego swapu (po opłacie protokołu)
+    pub yield_lst: u64,
+    /// LST opłaty protokołu (przelew na konto opłat)
+    pub fee_lst: u64,
+    pub fee_bps: u16,
+    pub fee_tier: Option<u8>,
+}
+
+impl BatchSettlement {
+    /// Łączny odpływ LST ze skarbca dla pozycji (opłaty + LST zysku)
+    pub fn vault_outflow(&self) -> Result<u64> {
+        self.management_lst
+            .checked_add(self.yield_lst)
+            .and_then(|total| total.checked_add(self.fee_lst))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+}
+
+/// Klasyfikacja pozycji w paczce (przed rozliczeniem opłat)
+///
+/// Publiczna - keeper wybiera tymi samymi regułami pozycje do paczki.
+pub fn batch_position_status(
+    user_stake: &UserStake,
+    dividend: u64,
+    payout_mint: &Pubkey,
+    current_rate: u64,
+    current_timestamp: i64
+) -> Result<CrankClaimStatus> {
+    let (paid_dividend, _) = split_dividend(user_stake, dividend)?;
+    let status = if !user_stake.auto_claim_enabled
+        || user_stake.payout_schedule == PayoutSchedule::Disabled
+    {
+        CrankClaimStatus::AutoClaimDisabled
+    } else if user_stake.payout_mode != PayoutMode::Usdc {
+        CrankClaimStatus::UnsupportedPayoutMode
+    } else if user_stake.payout_mint != *payout_mint {
+        CrankClaimStatus::PayoutMintMismatch
+    } else if current_timestamp < user_stake.next_payout_date {
+        CrankClaimStatus::NotDue
+    } else if dividend == 0
+        || dividend < user_stake.min_dividend_amount
+        || math::dividend_to_msol(paid_dividend, current_rate)? == 0
+    {
+        CrankClaimStatus::BelowMinimum
+    } else {
+        CrankClaimStatus::Paid
+    };
+    Ok(status)
+}
+
+/// Rozlicza wypłacaną pozycję paczki bez kont (stan pozycji, skarbca i statystyk)
+///
+/// Kolejność jak w pojedynczej wypłacie: opłata za zarządzanie, dywidenda
+/// i jej wypłacana część, spalenie udziałów zysku, opłata protokołu.
+/// Dywidenda po opłacie za zarządzanie klasyfikowana ponownie - pozycja
+/// niegotowa zwraca status pominięcia i samą opłatę (bez zmiany harmonogramu).
+/// `available_lst` - saldo skarbca pomniejszone o LST zarezerwowane wcześniej
+/// w tej paczce. Publiczna - keeper liczy nią kwotę swapu paczki.
+pub fn settle_batch_position(
+    config: &mut ProgramConfig,
+    fee_tiers: &FeeTiers,
+    user_stake: &mut UserStake,
+    vault: &mut Vault,
+    available_lst: u64,
+    current_rate: u64,
+    current_timestamp: i64
+) -> Result<BatchSettlement> {
+    let management = charge_management_fee(
+        config,
+        user_stake,
+        vault,
+        available_lst,
+        current_rate,
+        current_timestamp
+    )?;
+    let available_lst = available_lst
+        .checked_sub(management.lst)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let dividend = pending_dividend(user_stake, vault.total_shares, available_lst, current_rate)?;
+    let payout_mint = user_stake.payout_mint;
+    let status = batch_position_status(user_stake, dividend, &payout_mint, current_rate, current_timestamp)?;
+    if status != CrankClaimStatus::Paid {
+        return Ok(BatchSettlement {
+            status,
+            management_lst: management.lst,
+            paid_dividend: 0,
+            yield_lst: 0,
+            fee_lst: 0,
+            fee_bps: 0,
+            fee_tier: None,
+        });
+    }
+
+    let (paid_dividend, compounded) = split_dividend(user_stake, dividend)?;
+    let value = position_value(user_stake, vault.total_shares, available_lst, current_rate)?;
+    let yield_lst = burn_yield_shares(user_stake, vault, available_lst, paid_dividend, current_rate)?;
+
+    // Opłata protokołu z LST zysku, reszta do wspólnego swapu
+    let fee = protocol_fee(config, fee_tiers, value, paid_dividend, yield_lst)?;
+    record_dividend(user_stake, paid_dividend, compounded, current_rate, current_timestamp, true)?;
+    record_fee(user_stake, config, fee.lamports)?;
+
+    Ok(BatchSettlement {
+        status,
+        management_lst: management.lst,
+        paid_dividend,
+        yield_lst: yield_lst - fee.lst,
+        fee_lst: fee.lst,
+        fee_bps: fee.bps,
+        fee_tier: fee.tier,
+    })
+}
+
+pub fn batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchCrankClaim<'info>>,
+    position_count: u8,
+    route_data: Vec<u8>,
+) -> Result<()> {
+    require!(position_count > 0, ErrorCode::InvalidAccountConfig);
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+    let payout_mint_key = ctx.accounts.payout_token.mint;
+    let asset_mint = ctx.accounts.asset.mint;
+
+    // LST zysku zarezerwowane do sprzedaży nie wchodzi już w wycenę udziałów
+    let mut available_lst = ctx.accounts.vault_lst.amount;
+    let mut total_yield_lst: u64 = 0;
+    let mut total_fee_lst: u64 = 0;
+    let mut paid = Vec::with_capacity(position_count as usize);
+
+    // Grupy kont pozycji mają zmienną długość (zależną od liczby beneficjentów)
+    let mut cursor = 0usize;
+    for _ in 0..position_count {
+        require!(
+            ctx.remaining_accounts.len() >= cursor + 2,
+            ErrorCode::InvalidAccountConfig
+        );
+        let (stake_info, owner_payout_info) = (
+            &ctx.remaining_accounts[cursor],
+            &ctx.remaining_accounts[cursor + 1]
+        );
+        require!(
+            stake_info.is_writable && owner_payout_info.is_writable,
+            ErrorCode::InvalidAccountConfig
+        );
+
+        // 1. Weryfikacja kont pozycji
+        let mut user_stake: Account<'info, UserStake> = Account::try_from(stake_info)?;
+        let expected_stake = Pubkey::create_program_address(
+            &[b"user-stake", user_stake.user.as_ref(), asset_mint.as_ref(), &[user_stake.bump]],
+            ctx.program_id
+        ).map_err(|_| ErrorCode::InvalidAccountConfig)?;
+        require_keys_eq!(expected_stake, stake_info.key(), ErrorCode::InvalidAccountConfig);
+
+        let owner_payout: Account<'info, TokenAccount> = Account::try_from(owner_payout_info)?;
+        require_keys_eq!(owner_payout.mint, payout_mint_key, ErrorCode::InvalidPayoutMint);
+        require_keys_eq!(owner_payout.owner, user_stake.user, ErrorCode::InvalidAccountConfig);
+
+        // Konta beneficjentów (weryfikowane przy wypłacie w pay_recipients)
+        let beneficiary_count = user_stake.beneficiaries.len();
+        require!(
+            ctx.remaining_accounts.len() >= cursor + 2 + beneficiary_count,
+            ErrorCode::InvalidAccountConfig
+        );
+        let beneficiary_accounts = &ctx.remaining_accounts[cursor + 2..cursor + 2 + beneficiary_count];
+        cursor += 2 + beneficiary_count;
+
+        // 2. Klasyfikacja - pozycje niegotowe są pomijane
+        let dividend = pending_dividend(
+            &user_stake,
+            ctx.accounts.vault.total_shares,
+            available_lst,
+            current_rate
+        )?;
+        let status = batch_position_status(
+            &user_stake,
+            dividend,
+            &payout_mint_key,
+            current_rate,
+            current_timestamp
+        )?;
+
+        if status == CrankClaimStatus::Paid {
+            // 3. Opłata za zarządzanie, spalenie udziałów zysku i opłata protokołu
+            let settlement = settle_batch_position(
+                &mut ctx.accounts.config,
+                &ctx.accounts.fee_tiers,
+                &mut user_stake,
+                &mut ctx.accounts.vault,
+                available_lst,
+                current_rate,
+                current_timestamp
+            )?;
+            // LST zysku zarezerwowane do sprzedaży nie wchodzi już w wycenę udziałów
+            available_lst = available_lst
+                .checked_sub(settlement.vault_outflow()?)
+                .ok_or(ErrorCode::MathOverflow)?;
+            total_yield_lst = total_yield_lst
+                .checked_add(settlement.yield_lst)
+                .ok_or(ErrorCode::MathOverflow)?;
+            total_fee_lst = total_fee_lst
+                .checked_add(settlement.management_lst)
+                .and_then(|total| total.checked_add(settlement.fee_lst))
+                .ok_or(ErrorCode::MathOverflow)?;
+
+            user_stake.exit(ctx.program_id)?;
+            if settlement.status != CrankClaimStatus::Paid {
+                // Zysk po opłacie za zarządzanie poniżej minimum - tylko opłata
+                emit!(CrankClaimResult {
+                    user_stake: stake_info.key(),
+                    status: settlement.status,
+                    dividend: pending_dividend(
+                        &user_stake,
+                        ctx.accounts.vault.total_shares,
+                        available_lst,
+                        current_rate
+                    )?,
+                    bounty: 0,
+                    fee_bps: 0,
+                    fee_tier: None,
+                });
+                continue;
+            }
+            paid.push((
+                stake_info.key(),
+                owner_payout_info,
+                user_stake.beneficiaries.clone(),
+                beneficiary_accounts,
+                settlement.paid_dividend,
+                settlement.yield_lst,
+                settlement.fee_bps,
+                settlement.fee_tier
+            ));
+        } else {
+            emit!(CrankClaimResult {
+                user_stake: stake_info.key(),
+                status,
+                dividend,
+                bounty: 0,
+                fee_bps: 0,
+                fee_tier: None,
+            });
+        }
+    }
+
+    if paid.is_empty() {
+        // Opłaty za zarządzanie pozycji pominiętych po rozliczeniu
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_lst,
+            &ctx.accounts.fee_lst,
+            total_fee_lst,
+        )?;
+        msg!("Batch crank: 0 of {} positions paid", position_count);
+        return Ok(());
+    }
+
+    let route_accounts = &ctx.remaining_accounts[cursor..];
+
+    // 4. Jeden swap LST zysku wszystkich wypłacanych pozycji
+    let min_amount_out = oracle_min_out(
+        &ctx.accounts.config,
+        &ctx.accounts.sol_usd_oracle,
+        &ctx.accounts.lst_sol_oracle,
+        ctx.accounts.payout_token.quote,
+        total_yield_lst,
+        ctx.accounts.lst_mint.decimals,
+        ctx.accounts.payout_mint.decimals,
+        ctx.accounts.config.oracle_max_slippage_bps,
+    )?;
+    let vault_bump = ctx.accounts.vault.bump;
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[vault_bump]];
+    let swap = jupiter::swap_via_route(
+        &ctx.accounts.jupiter_program,
+        route_accounts,
+        route_data,
+        &mut ctx.accounts.vault_lst,
+        &mut ctx.accounts.vault_payout,
+        total_yield_lst,
+        min_amount_out,
+        &[vault_seeds],
+    )?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        total_fee_lst,
+    )?;
+
+    // 5. Podział tokenu wypłaty proporcjonalnie do LST zysku (zaokrąglenie w dół)
+    let mut total_bounty: u64 = 0;
+    for (stake_key, owner_payout_info, beneficiaries, beneficiary_accounts, dividend, yield_lst, fee_bps, fee_tier) in paid.iter() {
+        let payout_out = u64::try_from(
+            (swap.amount_out as u128) * (*yield_lst as u128) / total_yield_lst as u128
+        ).map_err(|_| ErrorCode::MathOverflow)?;
+        let (bounty, owner_amount) = math::split_keeper_bounty(payout_out, ctx.accounts.config.keeper_bounty_bps)?;
+
+        pay_recipients(
+            &ctx.accounts.token_program,
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_payout,
+            owner_payout_info,
+            beneficiaries,
+            beneficiary_accounts,
+            owner_amount,
+        )?;
+        total_bounty = total_bounty
+            .checked_add(bounty)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(CrankClaimResult {
+            user_stake: *stake_key,
+            status: CrankClaimStatus::Paid,
+            dividend: *dividend,
+            bounty,
+            fee_bps: *fee_bps,
+            fee_tier: *fee_tier,
+        });
+    }
+
+    pay_from_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_payout,
+        &ctx.accounts.keeper_payout.to_account_info(),
+        total_bounty,
+    )?;
+
+    msg!(
+        "Batch crank: {} of {} positions paid ({} LST -> {} of {})",
+        paid.len(),
+        position_count,
+        swap.amount_in,
+        swap.amount_out,
+        payout_mint_key
+    );
+    Ok(())
+}
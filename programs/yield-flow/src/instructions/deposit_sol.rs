@@ -0,0 +1,94 @@
+// Plik implementujący depozyt SOL -> mSOL przez Marinade
+//
+// Główne funkcjonalności:
+// - SOL użytkownika trafia do Marinade (CPI deposit), mSOL na konto użytkownika
+// - mSOL nie trafia do skarbca - pozycję tworzy initialize_user_stake
+//   (lub deposit_lst dla istniejącej pozycji)
+//
+// Struktury:
+// - DepositSol: Konta wymagane do depozytu
+//   * user: Podpisujący użytkownik (źródło SOL)
+//   * user_msol: Docelowe konto mSOL użytkownika
+//   * marinade_state / msol_mint: State i mint mSOL z ProgramConfig
+//   * liq_pool_* / reserve_pda: Konta Marinade (weryfikowane przez Marinade)
+//
+// Bezpieczeństwo:
+// - Wstrzymywany pauzą globalną lub pauzą depozytów (PauseTarget::Deposits)
+// - Program Marinade, State i mint mSOL zgodne z ProgramConfig
+// - Konto mSOL musi należeć do użytkownika
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use marinade_finance::State as MarinadeState;
+use crate::{
+    state::{PauseTarget, ProgramConfig},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct DepositSol<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Deposits) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        token::mint = config.msol_mint,
+        token::authority = user
+    )]
+    pub user_msol: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub marinade_state: Account<'info, MarinadeState>,
+
+    #[account(mut, address = config.msol_mint @ ErrorCode::InvalidMsolMint)]
+    pub msol_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub liq_pool_sol: AccountInfo<'info>,
+    #[account(mut)]
+    pub liq_pool_msol: Account<'info, TokenAccount>,
+    /// CHECK: Verified by Marinade program
+    pub liq_pool_authority: AccountInfo<'info>,
+
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub reserve_pda: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = config.marinade_program @ ErrorCode::InvalidMarinadeProgram)]
+    pub marinade_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<DepositSol>, amount_lamports: u64) -> Result<()> {
+    require!(amount_lamports > 0, ErrorCode::InvalidAmount);
+
+    let cpi_accounts = marinade_finance::cpi::accounts::Deposit {
+        state: ctx.accounts.marinade_state.to_account_info(),
+        msol_mint: ctx.accounts.msol_mint.to_account_info(),
+        liq_pool_sol_leg_pda: ctx.accounts.liq_pool_sol.to_account_info(),
+        liq_pool_msol_leg: ctx.accounts.liq_pool_msol.to_account_info(),
+        liq_pool_msol_leg_authority: ctx.accounts.liq_pool_authority.to_account_info(),
+        reserve_pda: ctx.accounts.reserve_pda.to_account_info(),
+        transfer_from: ctx.accounts.user.to_account_info(),
+        mint_to: ctx.accounts.user_msol.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    };
+    marinade_finance::cpi::deposit(
+        CpiContext::new(ctx.accounts.marinade_program.to_account_info(), cpi_accounts),
+        amount_lamports
+    )?;
+
+    msg!("Deposited {} lamports to Marinade", amount_lamports);
+    Ok(())
+}
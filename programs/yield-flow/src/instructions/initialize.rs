@@ -1,24 +1,37 @@
 // Plik implementujący inicjalizację stakingu użytkownika
 //
 // Główne funkcje:
-// - Tworzy nowe konto UserStake dla użytkownika
-// - Inicjalizuje podstawowe wartości stakingu
-// - Weryfikuje poprawność programu Marinade
+// - Tworzy nowe konto UserStake dla użytkownika i wybranego aktywa LST
+// - Przelewa początkowy depozyt LST do skarbca aktywa
+// - Emituje udziały w skarbcu i ustala kurs bazowy pozycji
+// - Wymaga aktywa zarejestrowanego i włączonego w rejestrze
+// - Token wypłaty pozycji domyślnie USDC z ProgramConfig (zmiana: set_payout_token)
+// - Domyślnie wypłacana cała dywidenda (payout_bps = MAX_BPS, zmiana: set_schedule)
 //
 // Struktury:
 // - InitializeUserStake: Konta wymagane do inicjalizacji
 //   * user_stake: Nowe konto stakingowe (PDA)
 //   * user: Podpisujący użytkownik
-//   * config: Globalna konfiguracja programu
-//   * marinade_program: Weryfikowany program Marinade
+//   * config: Konfiguracja programu (domyślny token wypłaty)
+//   * asset: Wpis rejestru aktywa LST (mSOL, jitoSOL, bSOL, ...)
+//   * vault / vault_lst: Skarbiec aktywa i jego konto tokenowe
+//   * user_lst: Konto LST użytkownika (źródło depozytu)
+//   * pool_state: Stan puli aktywa (kurs LST)
 //
 // Bezpieczeństwo:
 // - Wymaga podpisu użytkownika
-// - Weryfikuje zgodność programu Marinade
-// - Konto inicjalizowane jako PDA z seedem "user-stake"
+// - Konto stanu puli weryfikowane względem rejestru aktywa
+// - Konto inicjalizowane jako PDA z seedami "user-stake", user, mint aktywa
+// - Udziały wynikają z faktycznie przelanego LST
 
 use anchor_lang::prelude::*;
-use crate::{state::{UserStake, ProgramConfig}, errors::ErrorCode};
+use anchor_spl::token::{Token, TokenAccount};
+use crate::{
+    state::{LstAsset, PauseTarget, ProgramConfig, UserStake, Vault},
+    utils::{constants::MAX_BPS, lst},
+    instructions::vault::transfer_into_vault,
+    errors::ErrorCode
+};
 
 #[derive(Accounts)]
 pub struct InitializeUserStake<'info> {
@@ -26,34 +39,76 @@ pub struct InitializeUserStake<'info> {
         init,
         payer = user,
         space = UserStake::LEN,
-        seeds = [b"user-stake", user.key().as_ref()],
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
         bump
     )]
     pub user_stake: Account<'info, UserStake>,
-    
+
     #[account(mut)]
     pub user: Signer<'info>,
-    
+
     #[account(
         seeds = [b"config"],
         bump = config.bump,
-        constraint = config.marinade_program == marinade_program.key() @ ErrorCode::InvalidMarinadeProgram
+        constraint = !config.is_paused(PauseTarget::Deposits) @ ErrorCode::OperationPaused
     )]
     pub config: Account<'info, ProgramConfig>,
-    
-    /// CHECK: Verified by constraint
-    pub marinade_program: AccountInfo<'info>,
+
+    #[account(
+        seeds = [LstAsset::SEED, asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.enabled @ ErrorCode::LstAssetDisabled
+    )]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = user
+    )]
+    pub user_lst: Account<'info, TokenAccount>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<InitializeUserStake>, msol_amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<InitializeUserStake>, amount: u64) -> Result<()> {
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+
     let user_stake = &mut ctx.accounts.user_stake;
     user_stake.user = ctx.accounts.user.key();
-    user_stake.msol_amount = msol_amount;
-    user_stake.base_sol_value = 1_000_000;
+    user_stake.asset = ctx.accounts.asset.mint;
+    user_stake.payout_mint = ctx.accounts.config.usdc_mint;
+    user_stake.payout_bps = MAX_BPS;
+    user_stake.shares = 0;
+    user_stake.base_sol_value = current_rate;
     user_stake.last_update = Clock::get()?.unix_timestamp;
     user_stake.bump = ctx.bumps.user_stake; // Correct bump access
-    
-    msg!("User stake initialized with bump: {}", user_stake.bump);
+
+    let shares = transfer_into_vault(
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.user_lst,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount,
+        current_rate,
+    )?;
+
+    msg!(
+        "User stake initialized with bump: {}, shares: {}",
+        ctx.accounts.user_stake.bump,
+        shares
+    );
     Ok(())
 }
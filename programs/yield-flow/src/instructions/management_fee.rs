@@ -0,0 +1,348 @@
+// Plik implementujący roczną opłatę za zarządzanie i podgląd opłat
+//
+// Główne funkcjonalności:
+// 1. Naliczanie opłaty za zarządzanie
+//    - Stawka roczna management_fee_bps z ProgramConfig (gdy fees_enabled)
+//    - Podstawa: kapitał pozycji w SOL po kursie bazowym (bez niewypłaconego zysku)
+//    - Naliczana liniowo za każdą sekundę od UserStake.last_update
+//
+// 2. Rozliczenie (charge_management_fee)
+//    - Wywoływane automatycznie przy każdej wypłacie dywidendy, dopłacie
+//      LST, wypłacie kapitału i kapitalizacji
+//    - last_update przesuwany tylko po pobraniu niezerowej opłaty (lub gdy
+//      opłata nie jest naliczana) - częste wywołania nie zerują naliczenia
+//    - LST opłaty = opłata / aktualny kurs (zaokrąglenie w dół), spalenie
+//      udziałów (zaokrąglenie w górę), przelew na konto LST właściciela opłat
+//      (pay_protocol_fee) wykonuje wywołujący
+//    - Opłata wliczana do total_fees pozycji i total_fees_collected programu
+//
+// 3. Podgląd opłat (preview_fees - widok)
+//    - Naliczona do teraz opłata za zarządzanie
+//    - Oczekująca dywidenda, jej wypłacana część i opłata protokołu od niej
+//      (stawka i próg z FeeTiers wg wartości pozycji)
+//    - Wynik zwracany jako dane zwrotne instrukcji (FeePreview)
+//
+// Struktury:
+// - PreviewFees: Konta wymagane do podglądu (tylko odczyt)
+// - FeePreview: Wynik podglądu
+// - ManagementFee: Opłata naliczona dla pozycji (lamporty + LST)
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::{
+    state::{FeeTiers, LstAsset, ProgramConfig, UserStake, Vault},
+    utils::{constants::LAMPORTS_PER_SOL, lst, math},
+    instructions::claim::{pending_dividend, position_value, protocol_fee, record_fee, split_dividend},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct PreviewFees<'info> {
+    #[account(
+        seeds = [b"user-stake", user_stake.user.as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct FeePreview {
+    /// Aktualna wartość pozycji w lamportach
+    pub position_value: u64,
+    /// Naliczona opłata za zarządzanie w lamportach (od last_update do teraz)
+    pub management_fee: u64,
+    pub management_fee_bps: u16,
+    /// Oczekująca dywidenda w lamportach (po opłacie za zarządzanie)
+    pub pending_dividend: u64,
+    /// Wypłacana część dywidendy (payout_bps pozycji)
+    pub paid_dividend: u64,
+    /// Opłata protokołu od wypłacanej części w lamportach
+    pub protocol_fee: u64,
+    pub protocol_fee_bps: u16,
+    /// Próg z FeeTiers (None = stała stawka fee_rate)
+    pub fee_tier: Option<u8>,
+}
+
+/// Opłata za zarządzanie naliczona dla pozycji
+pub(crate) struct ManagementFee {
+    /// Opłata w lamportach (statystyki)
+    pub lamports: u64,
+    /// Opłata w LST (przelew ze skarbca)
+    pub lst: u64,
+}
+
+/// Opłata za zarządzanie naliczona od last_update do `current_timestamp`
+pub(crate) fn accrued_management_fee(
+    config: &ProgramConfig,
+    user_stake: &UserStake,
+    total_shares: u64,
+    vault_balance: u64,
+    current_rate: u64,
+    current_timestamp: i64
+) -> Result<ManagementFee> {
+    let elapsed = current_timestamp.saturating_sub(user_stake.last_update);
+    if !management_fee_accrues(config) || elapsed <= 0 {
+        return Ok(ManagementFee { lamports: 0, lst: 0 });
+    }
+
+    // Kapitał w SOL po kursie bazowym - niewypłacony zysk nie jest obciążany
+    let position_lst = math::shares_to_msol(user_stake.shares, total_shares, vault_balance)?;
+    let principal = u64::try_from(
+        (position_lst as u128) * (user_stake.base_sol_value as u128) / LAMPORTS_PER_SOL as u128
+    ).map_err(|_| ErrorCode::MathOverflow)?;
+
+    let lamports = math::management_fee(principal, config.management_fee_bps, elapsed as u64)?;
+    let lst = math::dividend_to_msol(lamports, current_rate)?.min(position_lst);
+    Ok(ManagementFee { lamports, lst })
+}
+
+/// Rozlicza opłatę za zarządzanie: spala udziały i zapisuje opłatę
+///
+/// Przelew `lst` na konto właściciela opłat wykonuje wywołujący. Opłata
+/// zaokrąglona do 0 LST nie przesuwa last_update - naliczanie trwa od
+/// poprzedniego rozliczenia, więc częste wywołania nie omijają opłaty.
+pub(crate) fn charge_management_fee(
+    config: &mut ProgramConfig,
+    user_stake: &mut UserStake,
+    vault: &mut Vault,
+    vault_balance: u64,
+    current_rate: u64,
+    current_timestamp: i64
+) -> Result<ManagementFee> {
+    let fee = accrued_management_fee(
+        config,
+        user_stake,
+        vault.total_shares,
+        vault_balance,
+        current_rate,
+        current_timestamp
+    )?;
+
+    if fee.lst > 0 {
+        burn_fee_shares(user_stake, vault, vault_balance, fee.lst)?;
+        record_fee(user_stake, config, fee.lamports)?;
+        user_stake.last_update = current_timestamp;
+    } else if !management_fee_accrues(config) {
+        // Bez naliczania - po włączeniu opłaty nie obciąża okresu wstecz
+        user_stake.last_update = current_timestamp;
+    }
+
+    Ok(fee)
+}
+
+/// Czy opłata za zarządzanie jest naliczana
+fn management_fee_accrues(config: &ProgramConfig) -> bool {
+    config.fees_enabled && config.management_fee_bps > 0
+}
+
+/// Spala udziały odpowiadające `fee_lst` (zaokrąglenie w górę)
+pub(crate) fn burn_fee_shares(
+    user_stake: &mut UserStake,
+    vault: &mut Vault,
+    vault_balance: u64,
+    fee_lst: u64
+) -> Result<()> {
+    let burn_shares = math::msol_to_shares_ceil(fee_lst, vault.total_shares, vault_balance)?
+        .min(user_stake.shares);
+    user_stake.shares = user_stake.shares
+        .checked_sub(burn_shares)
+        .ok_or(ErrorCode::InsufficientShares)?;
+    vault.total_shares = vault.total_shares
+        .checked_sub(burn_shares)
+        .ok_or(ErrorCode::InsufficientShares)?;
+    Ok(())
+}
+
+pub fn preview_handler(ctx: Context<PreviewFees>) -> Result<FeePreview> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+    let config = &ctx.accounts.config;
+    let total_shares = ctx.accounts.vault.total_shares;
+    let vault_balance = ctx.accounts.vault_lst.amount;
+
+    // Symulacja rozliczenia na kopii pozycji - tak jak przy wypłacie
+    let mut user_stake = (*ctx.accounts.user_stake).clone();
+    let mut vault = (*ctx.accounts.vault).clone();
+    let management = accrued_management_fee(
+        config,
+        &user_stake,
+        total_shares,
+        vault_balance,
+        current_rate,
+        current_timestamp
+    )?;
+    burn_fee_shares(&mut user_stake, &mut vault, vault_balance, management.lst)?;
+    let vault_balance = vault_balance - management.lst;
+
+    let value = position_value(&user_stake, vault.total_shares, vault_balance, current_rate)?;
+    let dividend = pending_dividend(&user_stake, vault.total_shares, vault_balance, current_rate)?;
+    let (paid, _) = split_dividend(&user_stake, dividend)?;
+    let fee = protocol_fee(config, &ctx.accounts.fee_tiers, value, paid, 0)?;
+
+    Ok(FeePreview {
+        position_value: value,
+        management_fee: management.lamports,
+        management_fee_bps: if config.fees_enabled { config.management_fee_bps } else { 0 },
+        pending_dividend: dividend,
+        paid_dividend: paid,
+        protocol_fee: fee.lamports,
+        protocol_fee_bps: fee.bps,
+        fee_tier: fee.tier,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::SECONDS_PER_YEAR;
+
+    const RATE: u64 = 1_000_000_000;
+    const NOW: i64 = 1_700_000_000;
+    const POSITION: u64 = 1_000_000_000_000; // 1000 LST
+    const YEAR: i64 = SECONDS_PER_YEAR as i64;
+
+    fn config(fees_enabled: bool, management_fee_bps: u16) -> ProgramConfig {
+        ProgramConfig {
+            admin: Pubkey::new_unique(),
+            marinade_program: Pubkey::default(),
+            msol_mint: Pubkey::default(),
+            sanglass_program: Pubkey::default(),
+            usdc_mint: Pubkey::default(),
+            fee_account: Pubkey::new_unique(),
+            bump: 0,
+            fees_enabled,
+            fee_rate: 0,
+            keeper_bounty_bps: 0,
+            sol_usd_oracle: Pubkey::default(),
+            msol_sol_oracle: Pubkey::default(),
+            oracle_max_age_secs: 0,
+            oracle_max_conf_bps: 0,
+            oracle_max_slippage_bps: 0,
+            total_fees_collected: 0,
+            management_fee_bps,
+            pending_admin: None,
+            paused: false,
+            deposits_paused: false,
+            swaps_paused: false,
+            claims_paused: false,
+            withdrawals_paused: false,
+        }
+    }
+
+    fn stake(shares: u64, last_update: i64) -> UserStake {
+        UserStake {
+            shares,
+            base_sol_value: RATE,
+            last_update,
+            ..Default::default()
+        }
+    }
+
+    fn vault(total_shares: u64) -> Vault {
+        Vault {
+            mint: Pubkey::default(),
+            token_account: Pubkey::default(),
+            total_shares,
+            bump: 0,
+            token_bump: 0,
+        }
+    }
+
+    #[test]
+    fn nothing_accrues_without_management_fee() {
+        let position = stake(POSITION, NOW - YEAR);
+        for config in [config(false, 200), config(true, 0)] {
+            let fee = accrued_management_fee(&config, &position, POSITION, POSITION, RATE, NOW).unwrap();
+            assert_eq!((fee.lamports, fee.lst), (0, 0));
+        }
+    }
+
+    #[test]
+    fn nothing_accrues_without_elapsed_time() {
+        let config = config(true, 200);
+        for last_update in [NOW, NOW + 60] {
+            let fee = accrued_management_fee(&config, &stake(POSITION, last_update), POSITION, POSITION, RATE, NOW)
+                .unwrap();
+            assert_eq!((fee.lamports, fee.lst), (0, 0));
+        }
+    }
+
+    #[test]
+    fn fee_is_charged_on_principal_at_base_rate() {
+        // Kurs wzrósł o 10% - zysk nie jest obciążany, LST opłaty po aktualnym kursie
+        let fee = accrued_management_fee(
+            &config(true, 200),
+            &stake(POSITION, NOW - YEAR),
+            POSITION,
+            POSITION,
+            1_100_000_000,
+            NOW
+        ).unwrap();
+        assert_eq!(fee.lamports, 20_000_000_000);
+        assert_eq!(fee.lst, 18_181_818_181);
+    }
+
+    #[test]
+    fn fee_lst_is_capped_at_position() {
+        let fee = accrued_management_fee(
+            &config(true, 10_000),
+            &stake(POSITION, NOW - 2 * YEAR),
+            POSITION,
+            POSITION,
+            RATE,
+            NOW
+        ).unwrap();
+        assert_eq!(fee.lamports, 2 * POSITION);
+        assert_eq!(fee.lst, POSITION);
+    }
+
+    #[test]
+    fn zero_rounded_fee_keeps_accruing() {
+        // 1 LST przy 2% rocznie - opłata za sekundę zaokrąglona do zera
+        let mut config = config(true, 200);
+        let mut position = stake(1_000_000_000, NOW - 1);
+        let mut vault = vault(1_000_000_000);
+        let fee = charge_management_fee(&mut config, &mut position, &mut vault, 1_000_000_000, RATE, NOW).unwrap();
+        assert_eq!(fee.lst, 0);
+        assert_eq!(position.last_update, NOW - 1);
+        assert_eq!(position.shares, 1_000_000_000);
+
+        // Po roku naliczenie obejmuje cały okres od poprzedniego rozliczenia
+        let later = NOW - 1 + YEAR;
+        let fee = charge_management_fee(&mut config, &mut position, &mut vault, 1_000_000_000, RATE, later).unwrap();
+        assert_eq!(fee.lst, 20_000_000);
+        assert_eq!(position.last_update, later);
+        assert_eq!(position.shares, 980_000_000);
+        assert_eq!(vault.total_shares, 980_000_000);
+        assert_eq!(config.total_fees_collected, 20_000_000);
+    }
+
+    #[test]
+    fn disabled_fee_advances_last_update() {
+        // Po włączeniu opłaty okres bez naliczania nie jest obciążany wstecz
+        let mut config = config(false, 200);
+        let mut position = stake(POSITION, NOW - YEAR);
+        let mut vault = vault(POSITION);
+        charge_management_fee(&mut config, &mut position, &mut vault, POSITION, RATE, NOW).unwrap();
+        assert_eq!(position.last_update, NOW);
+        assert_eq!(position.shares, POSITION);
+    }
+}
@@ -2,17 +2,62 @@
 //
 // Eksportuje wszystkie instrukcje programu pogrupowane w moduły:
 // - admin: Operacje administracyjne programu
+// - beneficiaries: Podział wypłaty między beneficjentów
 // - claim: Logika wypłat dywidend
+// - claim_sol: Wypłata dywidend w SOL (Marinade liquid unstake)
+// - compound: Kapitalizacja zysku (auto-compound) i projekcja sald
+// - crank: Auto-wypłaty wywoływane przez keeperów (z nagrodą)
+// - deposit_sol: Depozyt SOL -> mSOL przez Marinade
 // - initialize: Inicjalizacja kont użytkowników
+// - management_fee: Roczna opłata za zarządzanie i podgląd opłat
+// - payout_token: Wybór tokenu wypłaty z listy dozwolonych
+// - roles: Role operacyjne programu (nadawane przez admina)
+// - set_schedule: Harmonogram wypłat użytkownika
+// - swap: Swap mSOL -> USDC przez Jupiter
+// - timelock: Opóźnione zmiany krytycznych adresów konfiguracji
+// - unstake_ticket: Wypłata w SOL przez bilety opóźnionego unstake Marinade
+// - update_config: Wypłaty do Sanglass (rola sanglass_operator)
+// - vault: Skarbce LST (jeden na aktywo) i depozyty
+// - withdraw: Wypłata kapitału i zamknięcie pozycji
 //
 // Każdy podmoduł zawiera:
 // - Struktury Accounts z wymaganymi kontami
 // - Funkcje handlerów wykonujące logikę instrukcji
 // - Powiązane typy danych i walidacje
 pub mod admin;
+pub mod beneficiaries;
 pub mod claim;
+pub mod claim_sol;
+pub mod compound;
+pub mod crank;
+pub mod deposit_sol;
 pub mod initialize;
+pub mod management_fee;
+pub mod payout_token;
+pub mod roles;
+pub mod set_schedule;
+pub mod swap;
+pub mod timelock;
+pub mod unstake_ticket;
+pub mod update_config;
+pub mod vault;
+pub mod withdraw;
 
 pub use admin::*;
+pub use beneficiaries::*;
 pub use claim::*;
+pub use claim_sol::*;
+pub use compound::*;
+pub use crank::*;
+pub use deposit_sol::*;
 pub use initialize::*;
+pub use management_fee::*;
+pub use payout_token::*;
+pub use roles::*;
+pub use set_schedule::*;
+pub use swap::*;
+pub use timelock::*;
+pub use unstake_ticket::*;
+pub use update_config::*;
+pub use vault::*;
+pub use withdraw::*;
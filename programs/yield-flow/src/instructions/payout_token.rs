@@ -0,0 +1,42 @@
+// Plik implementujący wybór tokenu wypłaty dywidendy przez użytkownika
+//
+// Główne funkcje:
+// - Ustawia payout_mint pozycji (token, na który sprzedawane jest LST zysku
+//   w trybie wypłaty Usdc - USDC, USDT, PYUSD, wSOL, ...)
+// - Token musi być na liście dozwolonych (PayoutToken) i włączony
+//
+// Struktury:
+// - SetPayoutToken: Konta wymagane do zmiany tokenu wypłaty
+//   * user_stake: Konto stakingowe użytkownika (mutable)
+//   * user: Podpisujący użytkownik (właściciel)
+//   * payout_token: Wpis listy dozwolonych tokenów wypłaty
+//
+// Bezpieczeństwo:
+// - Weryfikuje właściciela konta (has_one constraint)
+// - Wpis tokenu weryfikowany jako PDA programu
+
+use anchor_lang::prelude::*;
+use crate::{
+    state::{PayoutToken, UserStake},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct SetPayoutToken<'info> {
+    #[account(mut, has_one = user)]
+    pub user_stake: Account<'info, UserStake>,
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [PayoutToken::SEED, payout_token.mint.as_ref()],
+        bump = payout_token.bump,
+        constraint = payout_token.enabled @ ErrorCode::PayoutTokenDisabled
+    )]
+    pub payout_token: Account<'info, PayoutToken>,
+}
+
+pub fn handler(ctx: Context<SetPayoutToken>) -> Result<()> {
+    ctx.accounts.user_stake.payout_mint = ctx.accounts.payout_token.mint;
+    msg!("Payout token set to {}", ctx.accounts.payout_token.mint);
+    Ok(())
+}
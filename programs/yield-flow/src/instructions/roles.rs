@@ -0,0 +1,109 @@
+// Plik implementujący zarządzanie rolami operacyjnymi programu
+//
+// Główne funkcjonalności:
+// 1. Inicjalizacja tabeli ról (admin, jednorazowo)
+//    - Wszystkie role przypisane początkowo do admina - zachowanie programu
+//      bez zmian do czasu przekazania ról kluczom operacyjnym
+//
+// 2. Nadanie roli (set_role)
+//    - Admin przypisuje rolę dowolnemu kluczowi (zastępuje poprzedniego posiadacza)
+//
+// 3. Odebranie roli (revoke_role)
+//    - Rola ustawiana na Pubkey::default() - instrukcje roli niedostępne
+//      do czasu ponownego nadania
+//
+// Struktury:
+// - InitializeRoles: Konta wymagane do utworzenia tabeli ról
+// - UpdateRole: Konta wymagane do nadania / odebrania roli
+// - RoleAssigned / RoleRevoked: Eventy zmian ról
+//
+// Bezpieczeństwo:
+// - Wszystkie operacje wymagają podpisu admina
+// - Role nie dają dostępu do zmiany admina ani krytycznych adresów programu
+
+use anchor_lang::prelude::*;
+use crate::{
+    state::{ProgramConfig, Role, Roles},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct InitializeRoles<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = Roles::LEN,
+        seeds = [Roles::SEED],
+        bump
+    )]
+    pub roles: Account<'info, Roles>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [Roles::SEED], bump = roles.bump)]
+    pub roles: Account<'info, Roles>,
+}
+
+#[event]
+pub struct RoleAssigned {
+    pub role: Role,
+    pub holder: Pubkey,
+    pub previous_holder: Pubkey,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub role: Role,
+    pub previous_holder: Pubkey,
+}
+
+pub fn initialize_roles_handler(ctx: Context<InitializeRoles>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+    let roles = &mut ctx.accounts.roles;
+    roles.fee_manager = admin;
+    roles.pauser = admin;
+    roles.registry_manager = admin;
+    roles.sanglass_operator = admin;
+    roles.bump = ctx.bumps.roles;
+
+    msg!("Roles initialized (all roles held by admin {})", admin);
+    Ok(())
+}
+
+pub fn set_role_handler(ctx: Context<UpdateRole>, role: Role, holder: Pubkey) -> Result<()> {
+    require_keys_neq!(holder, Pubkey::default(), ErrorCode::InvalidRoleHolder);
+
+    let roles = &mut ctx.accounts.roles;
+    let previous_holder = roles.holder(role);
+    roles.set_holder(role, holder);
+
+    msg!("Role {:?} assigned: {} -> {}", role, previous_holder, holder);
+    emit!(RoleAssigned {
+        role,
+        holder,
+        previous_holder,
+    });
+    Ok(())
+}
+
+pub fn revoke_role_handler(ctx: Context<UpdateRole>, role: Role) -> Result<()> {
+    let roles = &mut ctx.accounts.roles;
+    let previous_holder = roles.holder(role);
+    roles.set_holder(role, Pubkey::default());
+
+    msg!("Role {:?} revoked from {}", role, previous_holder);
+    emit!(RoleRevoked {
+        role,
+        previous_holder,
+    });
+    Ok(())
+}
@@ -11,16 +11,30 @@
 //   * user: Podpisujący użytkownik (właściciel)
 //
 // - SetScheduleParams: Parametry konfiguracyjne
-//   * schedule: Nowy harmonogram wypłat
+//   * schedule: Nowy harmonogram wypłat (Weekly/Monthly zawierają PayoutTime:
+//     lokalną godzinę wypłaty i przesunięcie UTC w minutach)
 //   * auto_claim: Czy automatyczne wypłaty są aktywne
 //   * min_amount: Minimalna kwota wypłaty
+//   * payout_mode: Waluta wypłaty (Usdc przez Jupiter / Sol przez liquid unstake /
+//     DelayedSol przez bilet opóźnionego unstake)
+//   * max_unstake_fee_bps: Maksymalna opłata liquid unstake w trybie Sol
+//   * auto_compound: Czy zysk może być kapitalizowany (niezależnie od harmonogramu)
+//   * payout_bps: Wypłacana część dywidendy (10_000 = całość, reszta kapitalizowana)
 //
 // Bezpieczeństwo:
 // - Weryfikuje właściciela konta (has_one constraint)
-// - Oblicza nową datę wypłaty na podstawie harmonogramu
+// - Waliduje harmonogram (dzień, godzina, przesunięcie UTC)
+// - Waliduje limit opłaty unstake (<= MAX_BPS)
+// - Tryby Sol / DelayedSol wymagają pustej listy beneficjentów (wypłata SOL
+//   trafia w całości do właściciela)
+// - Oblicza nową datę wypłaty na podstawie harmonogramu (zapisywana w UTC)
 
 use anchor_lang::prelude::*;
-use crate::{state::UserStake, utils::ScheduleCalculator};
+use crate::{
+    state::{UserStake, PayoutSchedule, PayoutMode},
+    utils::{constants::MAX_BPS, schedule::ScheduleCalculator},
+    errors::ErrorCode
+};
 use anchor_lang::AnchorDeserialize;
 
 #[derive(Accounts)]
@@ -35,14 +49,33 @@ pub struct SetScheduleParams {
     pub schedule: PayoutSchedule,
     pub auto_claim: bool,
     pub min_amount: u64,
+    pub payout_mode: PayoutMode,
+    pub max_unstake_fee_bps: u16,
+    pub auto_compound: bool,
+    pub payout_bps: u16,
 }
 
 pub fn handler(ctx: Context<SetSchedule>, params: SetScheduleParams) -> Result<()> {
+    ScheduleCalculator::validate_schedule(&params.schedule)?;
+    require!(params.max_unstake_fee_bps <= MAX_BPS, ErrorCode::InvalidUnstakeFee);
+    require!(
+        params.payout_bps > 0 && params.payout_bps <= MAX_BPS,
+        ErrorCode::InvalidPayoutBps
+    );
+
     let user_stake = &mut ctx.accounts.user_stake;
+    require!(
+        params.payout_mode == PayoutMode::Usdc || user_stake.beneficiaries.is_empty(),
+        ErrorCode::BeneficiariesRequireTokenPayout
+    );
     
     user_stake.payout_schedule = params.schedule;
     user_stake.auto_claim_enabled = params.auto_claim;
     user_stake.min_dividend_amount = params.min_amount;
+    user_stake.payout_mode = params.payout_mode;
+    user_stake.max_unstake_fee_bps = params.max_unstake_fee_bps;
+    user_stake.auto_compound = params.auto_compound;
+    user_stake.payout_bps = params.payout_bps;
     
     // Oblicz nową datę wypłaty
     user_stake.next_payout_date = ScheduleCalculator::calculate_next_payout(
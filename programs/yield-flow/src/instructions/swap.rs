@@ -0,0 +1,166 @@
+// Plik implementujący swap mSOL -> USDC przez Jupiter
+//
+// Główne funkcjonalności:
+// - Klient przygotowuje trasę (Jupiter quote + swap-instructions)
+// - Program wykonuje CPI do Jupitera z danymi trasy i remaining_accounts
+// - Minimalna ilość USDC liczona z cen Pyth (SOL/USD * mSOL/SOL) minus
+//   slippage_bps użytkownika - trasa nie może ustalić własnej podłogi
+// - Wynik weryfikowany przez różnicę sald kont użytkownika
+//
+// Struktury:
+// - SwapMsolToUsdc: Konta wymagane do swapu
+//   * user: Podpisujący użytkownik (authority kont tokenowych)
+//   * user_msol: Źródłowe konto mSOL
+//   * user_usdc: Docelowe konto USDC
+//   * msol_mint / usdc_mint: Minty (decimale do wyceny)
+//   * sol_usd_oracle / msol_sol_oracle: Konta cen Pyth z ProgramConfig
+//   * jupiter_program: Program Jupiter (weryfikowany adres)
+//
+// Parametry:
+// - route_data: Dane instrukcji Jupitera (nieprzezroczyste)
+// - amount_in: Maksymalna ilość mSOL do wymiany
+// - slippage_bps: Dopuszczalne odchylenie od ceny z wyroczni
+//
+// Bezpieczeństwo:
+// - Konta tokenowe weryfikowane względem mintów z ProgramConfig
+// - Ceny nieaktualne lub z szerokim przedziałem ufności są odrzucane
+// - SlippageExceeded gdy saldo USDC wzrosło mniej niż podłoga z wyroczni
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    state::{PauseTarget, PayoutQuote, ProgramConfig},
+    utils::{jupiter::{self, JUPITER_PROGRAM_ID}, oracle},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct SwapMsolToUsdc<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Swaps) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        mut,
+        token::mint = config.msol_mint,
+        token::authority = user
+    )]
+    pub user_msol: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = config.usdc_mint,
+        token::authority = user
+    )]
+    pub user_usdc: Account<'info, TokenAccount>,
+
+    #[account(address = config.msol_mint @ ErrorCode::InvalidMsolMint)]
+    pub msol_mint: Account<'info, Mint>,
+
+    #[account(address = config.usdc_mint @ ErrorCode::InvalidUsdcMint)]
+    pub usdc_mint: Account<'info, Mint>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = config.sol_usd_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub sol_usd_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by oracle::read_price
+    #[account(address = config.msol_sol_oracle @ ErrorCode::InvalidOracleAccount)]
+    pub msol_sol_oracle: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = JUPITER_PROGRAM_ID @ ErrorCode::InvalidJupiterProgram)]
+    pub jupiter_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, SwapMsolToUsdc<'info>>,
+    route_data: Vec<u8>,
+    amount_in: u64,
+    slippage_bps: u16,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidAmount);
+
+    // Podłoga z wyroczni: amount_in * SOL/USD * mSOL/SOL - slippage
+    let min_amount_out = oracle_min_out(
+        &ctx.accounts.config,
+        &ctx.accounts.sol_usd_oracle,
+        &ctx.accounts.msol_sol_oracle,
+        PayoutQuote::Usd,
+        amount_in,
+        ctx.accounts.msol_mint.decimals,
+        ctx.accounts.usdc_mint.decimals,
+        slippage_bps,
+    )?;
+
+    let result = jupiter::swap_via_route(
+        &ctx.accounts.jupiter_program,
+        ctx.remaining_accounts,
+        route_data,
+        &mut ctx.accounts.user_msol,
+        &mut ctx.accounts.user_usdc,
+        amount_in,
+        min_amount_out,
+        &[],
+    )?;
+
+    msg!(
+        "Swapped {} mSOL -> {} USDC (oracle floor: {})",
+        result.amount_in,
+        result.amount_out,
+        min_amount_out
+    );
+    Ok(())
+}
+
+/// Minimalna ilość tokenu wyjściowego za `amount_in` LST według wyroczni Pyth
+///
+/// Dla `PayoutQuote::Usd` token wyjściowy wyceniany 1:1 z USD (SOL/USD * LST/SOL),
+/// dla `PayoutQuote::Sol` tylko LST/SOL - wyrocznia SOL/USD nie jest odczytywana.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn oracle_min_out(
+    config: &ProgramConfig,
+    sol_usd_oracle: &AccountInfo,
+    lst_sol_oracle: &AccountInfo,
+    quote: PayoutQuote,
+    amount_in: u64,
+    lst_decimals: u8,
+    out_decimals: u8,
+    slippage_bps: u16,
+) -> Result<u64> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let lst_sol = oracle::read_price(
+        lst_sol_oracle,
+        current_timestamp,
+        config.oracle_max_age_secs,
+        config.oracle_max_conf_bps
+    )?;
+    let prices = match quote {
+        PayoutQuote::Usd => vec![
+            oracle::read_price(
+                sol_usd_oracle,
+                current_timestamp,
+                config.oracle_max_age_secs,
+                config.oracle_max_conf_bps
+            )?,
+            lst_sol,
+        ],
+        PayoutQuote::Sol => vec![lst_sol],
+    };
+
+    oracle::min_out_from_prices(
+        amount_in,
+        lst_decimals,
+        out_decimals,
+        &prices,
+        slippage_bps
+    )
+}
@@ -0,0 +1,185 @@
+// Plik implementujący opóźnione (timelock) zmiany krytycznych adresów konfiguracji
+//
+// Główne funkcjonalności:
+// 1. Zakolejkowanie zmiany (admin)
+//    - Pola: marinade_program, usdc_mint, fee_account, sanglass_program,
+//      sol_usd_oracle, msol_sol_oracle
+//    - Tworzy PDA PendingChange z execute_after = teraz + CONFIG_TIMELOCK_SECS
+//    - Co najwyżej jedna oczekująca zmiana na pole (nowa wymaga anulowania starej)
+//
+// 2. Wykonanie zmiany (dowolny podpisujący)
+//    - Dozwolone dopiero po execute_after - użytkownicy mają czas na wyjście
+//      z pozycji przed wrogą zmianą
+//    - Konto PendingChange zamykane, rent wraca do proponującego
+//
+// 3. Anulowanie zmiany (admin)
+//    - Możliwe w dowolnym momencie przed wykonaniem
+//
+// Struktury:
+// - QueueConfigChange: Konta wymagane do zakolejkowania zmiany
+// - ExecuteConfigChange: Konta wymagane do wykonania zmiany
+// - CancelConfigChange: Konta wymagane do anulowania zmiany
+// - ConfigChangeQueued / ConfigChangeExecuted / ConfigChangeCancelled: Eventy
+//
+// Bezpieczeństwo:
+// - Kolejkowanie i anulowanie wymagają podpisu admina
+// - Wykonanie nie daje wpływu na wartość - tylko zapisuje zakolejkowany adres
+// - fee_account nie może być pusty, gdy opłaty są włączone
+
+use anchor_lang::prelude::*;
+use crate::{
+    state::{ConfigField, PendingChange, ProgramConfig},
+    utils::constants::CONFIG_TIMELOCK_SECS,
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+#[instruction(field: ConfigField)]
+pub struct QueueConfigChange<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        payer = admin,
+        space = PendingChange::LEN,
+        seeds = [PendingChange::SEED, &[field as u8]],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    pub executor: Signer<'info>,
+    #[account(mut, seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [PendingChange::SEED, &[pending_change.field as u8]],
+        bump = pending_change.bump,
+        has_one = proposer @ ErrorCode::InvalidAccountConfig,
+        close = proposer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    /// Odbiorca rentu zamykanego konta (admin, który zakolejkował zmianę)
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelConfigChange<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::Unauthorized)]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [PendingChange::SEED, &[pending_change.field as u8]],
+        bump = pending_change.bump,
+        has_one = proposer @ ErrorCode::InvalidAccountConfig,
+        close = proposer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub field: ConfigField,
+    pub new_value: Pubkey,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct ConfigChangeExecuted {
+    pub field: ConfigField,
+    pub old_value: Pubkey,
+    pub new_value: Pubkey,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub field: ConfigField,
+    pub new_value: Pubkey,
+}
+
+pub fn queue_handler(
+    ctx: Context<QueueConfigChange>,
+    field: ConfigField,
+    new_value: Pubkey,
+) -> Result<()> {
+    // Pusty fee_account dopuszczalny tylko przy wyłączonych opłatach (sprawdzane przy wykonaniu)
+    require!(
+        field == ConfigField::FeeAccount || new_value != Pubkey::default(),
+        ErrorCode::InvalidConfigChange
+    );
+
+    let queued_at = Clock::get()?.unix_timestamp;
+    let execute_after = queued_at
+        .checked_add(CONFIG_TIMELOCK_SECS)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.field = field;
+    pending_change.new_value = new_value;
+    pending_change.proposer = ctx.accounts.admin.key();
+    pending_change.queued_at = queued_at;
+    pending_change.execute_after = execute_after;
+    pending_change.bump = ctx.bumps.pending_change;
+
+    msg!("Config change queued: {:?} -> {} (executable after {})", field, new_value, execute_after);
+    emit!(ConfigChangeQueued {
+        field,
+        new_value,
+        execute_after,
+    });
+    Ok(())
+}
+
+pub fn execute_handler(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+    require!(
+        Clock::get()?.unix_timestamp >= pending_change.execute_after,
+        ErrorCode::TimelockNotElapsed
+    );
+
+    let field = pending_change.field;
+    let new_value = pending_change.new_value;
+    let config = &mut ctx.accounts.config;
+    let old_value = match field {
+        ConfigField::MarinadeProgram => std::mem::replace(&mut config.marinade_program, new_value),
+        ConfigField::UsdcMint => std::mem::replace(&mut config.usdc_mint, new_value),
+        ConfigField::FeeAccount => {
+            require!(
+                !config.fees_enabled || new_value != Pubkey::default(),
+                ErrorCode::InvalidFeeAccount
+            );
+            std::mem::replace(&mut config.fee_account, new_value)
+        }
+        ConfigField::SanglassProgram => std::mem::replace(&mut config.sanglass_program, new_value),
+        ConfigField::SolUsdOracle => std::mem::replace(&mut config.sol_usd_oracle, new_value),
+        ConfigField::MsolSolOracle => std::mem::replace(&mut config.msol_sol_oracle, new_value),
+    };
+
+    msg!("Config change executed: {:?} {} -> {}", field, old_value, new_value);
+    emit!(ConfigChangeExecuted {
+        field,
+        old_value,
+        new_value,
+    });
+    Ok(())
+}
+
+pub fn cancel_handler(ctx: Context<CancelConfigChange>) -> Result<()> {
+    let pending_change = &ctx.accounts.pending_change;
+
+    msg!("Config change cancelled: {:?} -> {}", pending_change.field, pending_change.new_value);
+    emit!(ConfigChangeCancelled {
+        field: pending_change.field,
+        new_value: pending_change.new_value,
+    });
+    Ok(())
+}
@@ -0,0 +1,397 @@
+// Plik obsługujący wypłaty w SOL przez opóźniony unstake Marinade (bilety)
+//
+// Główne funkcjonalności:
+// 1. Zlecenie biletu przy wypłacie dywidendy (tryb PayoutMode::DelayedSol)
+//    - Tryby Auto/Manual jak w claim.rs (te same warunki walidacji)
+//    - mSOL zysku = dywidenda / aktualny kurs (zaokrąglenie w dół)
+//    - spalenie udziałów odpowiadających mSOL zysku (zaokrąglenie w górę)
+//    - mSOL zysku zaokrąglone do 0: bez biletu, cała dywidenda kapitalizowana
+//    - opłata protokołu (jak w claim.rs) pobierana w mSOL przed zleceniem
+//    - program tworzy konto biletu Marinade (PDA "marinade-ticket") i zleca
+//      order_unstake - bez opłaty liquid unstake
+//    - bilet zapisywany jako UnstakeTicket przypisany do pozycji
+//
+// 2. Odbiór biletu po zakończeniu epoki
+//    - Warunek gotowości jak w Marinade (kolejna epoka + dodatkowe oczekiwanie)
+//    - CPI claim: SOL trafia do beneficjenta biletu (PDA Vault)
+//    - Otrzymane SOL (różnica sald) przekazywane właścicielowi
+//    - Konto UnstakeTicket zamykane, rent wraca do właściciela
+//
+// Struktury:
+// - ClaimDividendTicket: Konta wymagane do zlecenia biletu
+// - RedeemUnstakeTicket: Konta wymagane do odbioru biletu
+// - UnstakeTicketOrdered / UnstakeTicketRedeemed: Eventy
+//
+// Lista biletów:
+// - Konta UnstakeTicket użytkownika (memcmp na polu user), patrz
+//   state::UnstakeTicket
+//
+// Bezpieczeństwo:
+// - Wymaga podpisu właściciela pozycji
+// - Tylko aktywo Marinade z rejestru (program i State z wpisu aktywa)
+// - Odbiór tylko przez właściciela biletu, kwota z różnicy sald skarbca
+// - Bilet tylko dla pozycji bez beneficjentów (SOL w całości do właściciela)
+// - Odbiór biletu nie wymaga istniejącej pozycji (np. po pełnej wypłacie)
+
+use anchor_lang::{prelude::*, system_program::{self, CreateAccount}};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    state::{FeeTiers, LstAsset, PauseTarget, PayoutMode, ProgramConfig, RateReader, UnstakeTicket, UserStake, Vault},
+    utils::{constants::LAMPORTS_PER_SOL, lst, marinade::{self, TICKET_ACCOUNT_LEN}},
+    instructions::{
+        claim::{
+            burn_yield_shares, compound_unpaid_dividend, pay_protocol_fee, pending_dividend,
+            position_value, protocol_fee, record_dividend, record_fee, split_dividend,
+            validate_claim, ClaimMode, DividendClaimed
+        },
+        management_fee::charge_management_fee
+    },
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct ClaimDividendTicket<'info> {
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Claims) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// Tylko aktywo Marinade (mSOL)
+    #[account(
+        seeds = [LstAsset::SEED, asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.rate_reader == RateReader::Marinade @ ErrorCode::UnsupportedLstAsset
+    )]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_msol: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = UnstakeTicket::LEN,
+        seeds = [
+            UnstakeTicket::SEED,
+            user_stake.key().as_ref(),
+            &user_stake.ticket_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub ticket: Account<'info, UnstakeTicket>,
+
+    /// CHECK: Tworzone w handlerze (właściciel = Marinade), inicjalizowane przez order_unstake
+    #[account(
+        mut,
+        seeds = [UnstakeTicket::MARINADE_SEED, ticket.key().as_ref()],
+        bump
+    )]
+    pub marinade_ticket: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(address = asset.pool_program @ ErrorCode::InvalidMarinadeProgram)]
+    pub marinade_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by address, parsed by lst::read_rate
+    #[account(mut, address = asset.pool_state @ ErrorCode::InvalidMarinadeState)]
+    pub marinade_state: AccountInfo<'info>,
+
+    #[account(mut, address = asset.mint @ ErrorCode::InvalidMsolMint)]
+    pub msol_mint: Account<'info, Mint>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemUnstakeTicket<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = user @ ErrorCode::Unauthorized,
+        has_one = marinade_ticket @ ErrorCode::InvalidAccountConfig,
+        close = user
+    )]
+    pub ticket: Account<'info, UnstakeTicket>,
+
+    /// Tylko aktywo Marinade (mSOL)
+    #[account(
+        seeds = [LstAsset::SEED, asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.rate_reader == RateReader::Marinade @ ErrorCode::UnsupportedLstAsset
+    )]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: Verified by address
+    #[account(address = asset.pool_program @ ErrorCode::InvalidMarinadeProgram)]
+    pub marinade_program: AccountInfo<'info>,
+
+    /// CHECK: Verified by address
+    #[account(mut, address = asset.pool_state @ ErrorCode::InvalidMarinadeState)]
+    pub marinade_state: AccountInfo<'info>,
+
+    /// CHECK: Verified by Marinade program
+    #[account(mut)]
+    pub reserve_pda: AccountInfo<'info>,
+
+    /// CHECK: Zgodność z biletem weryfikowana przez has_one
+    #[account(mut)]
+    pub marinade_ticket: AccountInfo<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct UnstakeTicketOrdered {
+    pub user: Pubkey,
+    pub ticket: Pubkey,
+    pub msol_amount: u64,
+    pub lamports_amount: u64,
+    pub created_epoch: u64,
+}
+
+#[event]
+pub struct UnstakeTicketRedeemed {
+    pub user: Pubkey,
+    pub ticket: Pubkey,
+    pub lamports: u64,
+}
+
+pub fn order_handler(ctx: Context<ClaimDividendTicket>, mode: ClaimMode) -> Result<()> {
+    let clock = Clock::get()?;
+
+    // 1. Walidacja pozycji - przed opłatami i CPI
+    require!(
+        ctx.accounts.user_stake.payout_mode == PayoutMode::DelayedSol,
+        ErrorCode::PayoutModeMismatch
+    );
+    // SOL trafia w całości do właściciela - beneficjenci tylko przy wypłacie w tokenie
+    require!(
+        ctx.accounts.user_stake.beneficiaries.is_empty(),
+        ErrorCode::BeneficiariesRequireTokenPayout
+    );
+
+    // 2. Oblicz dywidendę
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.marinade_state)?;
+
+    // Opłata za zarządzanie rozliczana przed dywidendą (przelew po walidacji)
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_msol.amount,
+        current_rate,
+        clock.unix_timestamp
+    )?;
+    let vault_balance = ctx.accounts.vault_msol.amount - management.lst;
+    let dividend = pending_dividend(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+
+    // 3. Walidacja wypłaty
+    validate_claim(&ctx.accounts.user_stake, &mode, dividend, clock.unix_timestamp)?;
+    require!(dividend > 0, ErrorCode::NoDividendToClaim);
+
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_msol,
+        &ctx.accounts.fee_lst,
+        management.lst,
+    )?;
+
+    // Bilet tylko na wypłacaną część zysku (reszta kapitalizowana)
+    let (paid, compounded) = split_dividend(&ctx.accounts.user_stake, dividend)?;
+    let value = position_value(
+        &ctx.accounts.user_stake,
+        ctx.accounts.vault.total_shares,
+        vault_balance,
+        current_rate
+    )?;
+    let yield_msol = burn_yield_shares(
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        vault_balance,
+        paid,
+        current_rate
+    )?;
+    if yield_msol == 0 {
+        // Wypłacana część poniżej 1 mSOL - bez biletu, całość kapitalizowana
+        ctx.accounts.ticket.close(ctx.accounts.user.to_account_info())?;
+        return compound_unpaid_dividend(
+            &mut ctx.accounts.user_stake,
+            dividend,
+            value,
+            current_rate,
+            clock.unix_timestamp,
+            matches!(mode, ClaimMode::Auto)
+        );
+    }
+    let fee = protocol_fee(&ctx.accounts.config, &ctx.accounts.fee_tiers, value, paid, yield_msol)?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_msol,
+        &ctx.accounts.fee_lst,
+        fee.lst,
+    )?;
+    let yield_msol = yield_msol - fee.lst;
+
+    // 4. Konto biletu Marinade (PDA programu, właściciel = Marinade)
+    let ticket_key = ctx.accounts.ticket.key();
+    let marinade_ticket_seeds: &[&[u8]] = &[
+        UnstakeTicket::MARINADE_SEED,
+        ticket_key.as_ref(),
+        &[ctx.bumps.marinade_ticket]
+    ];
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.user.to_account_info(),
+                to: ctx.accounts.marinade_ticket.to_account_info(),
+            },
+            &[marinade_ticket_seeds],
+        ),
+        ctx.accounts.rent.minimum_balance(TICKET_ACCOUNT_LEN),
+        TICKET_ACCOUNT_LEN as u64,
+        &ctx.accounts.marinade_program.key(),
+    )?;
+
+    // 5. Zlecenie unstake - beneficjentem biletu jest PDA Vault
+    let asset_mint = ctx.accounts.asset.mint;
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[ctx.accounts.vault.bump]];
+    marinade::order_unstake(
+        &ctx.accounts.marinade_program,
+        &ctx.accounts.marinade_state,
+        &ctx.accounts.msol_mint.to_account_info(),
+        &ctx.accounts.vault_msol.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.marinade_ticket,
+        &ctx.accounts.clock.to_account_info(),
+        &ctx.accounts.rent.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        yield_msol,
+        &[vault_seeds],
+    )?;
+
+    // 6. Zapis biletu i aktualizacja pozycji
+    let lamports_amount = u64::try_from(
+        (yield_msol as u128) * (current_rate as u128) / LAMPORTS_PER_SOL as u128
+    ).map_err(|_| ErrorCode::MathOverflow)?;
+
+    let ticket = &mut ctx.accounts.ticket;
+    ticket.user = ctx.accounts.user.key();
+    ticket.user_stake = ctx.accounts.user_stake.key();
+    ticket.marinade_ticket = ctx.accounts.marinade_ticket.key();
+    ticket.msol_amount = yield_msol;
+    ticket.lamports_amount = lamports_amount;
+    ticket.dividend = paid;
+    ticket.created_epoch = clock.epoch;
+    ticket.bump = ctx.bumps.ticket;
+
+    let user_stake = &mut ctx.accounts.user_stake;
+    user_stake.ticket_count = user_stake.ticket_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    record_dividend(
+        user_stake,
+        paid,
+        compounded,
+        current_rate,
+        clock.unix_timestamp,
+        matches!(mode, ClaimMode::Auto)
+    )?;
+    record_fee(user_stake, &mut ctx.accounts.config, fee.lamports)?;
+
+    emit!(UnstakeTicketOrdered {
+        user: ticket.user,
+        ticket: ticket_key,
+        msol_amount: yield_msol,
+        lamports_amount,
+        created_epoch: clock.epoch,
+    });
+    emit!(DividendClaimed {
+        user_stake: user_stake.key(),
+        dividend: paid,
+        compounded,
+        position_value: value,
+        fee: fee.lamports,
+        fee_bps: fee.bps,
+        fee_tier: fee.tier,
+    });
+    Ok(())
+}
+
+pub fn redeem_handler(ctx: Context<RedeemUnstakeTicket>) -> Result<()> {
+    require!(
+        ctx.accounts.ticket.is_ready(&Clock::get()?),
+        ErrorCode::TicketNotReady
+    );
+
+    // 1. Odbiór biletu - SOL trafia do PDA Vault (beneficjent)
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let lamports_before = vault_info.lamports();
+    marinade::claim_ticket(
+        &ctx.accounts.marinade_program,
+        &ctx.accounts.marinade_state,
+        &ctx.accounts.reserve_pda,
+        &ctx.accounts.marinade_ticket,
+        &vault_info,
+        &ctx.accounts.clock.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    let received = vault_info.lamports()
+        .checked_sub(lamports_before)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    // 2. Przekazanie SOL właścicielowi (saldo skarbca wraca do stanu sprzed odbioru)
+    **vault_info.try_borrow_mut_lamports()? -= received;
+    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += received;
+
+    emit!(UnstakeTicketRedeemed {
+        user: ctx.accounts.user.key(),
+        ticket: ctx.accounts.ticket.key(),
+        lamports: received,
+    });
+    Ok(())
+}
@@ -1,65 +1,61 @@
 // Moduł integracji z Sanglass - systemem wypłat na Solanie
 //
 // Główne funkcjonalności:
-// 1. `update_sanglass_program_handler` - Aktualizacja adresu programu Sanglass
-// 2. `process_withdrawal_handler` - Główna funkcja wypłat do Sanglass
+// 1. `process_withdrawal_handler` - Główna funkcja wypłat do Sanglass
+//    - Przelew tokenów z konta operatora na konto docelowe w systemie Sanglass
+//    - Program Sanglass weryfikowany adresem z ProgramConfig
+//
+// Adres programu Sanglass zmieniany wyłącznie przez timelock
+// (queue_config_change / execute_config_change z ConfigField::SanglassProgram)
 //
 // Bezpieczeństwo:
-// - Wszystkie operacje wymagają autoryzacji administratora
+// - Wypłaty wymagają podpisu posiadacza roli sanglass_operator
+// - Źródłem jest konto tokenowe operatora - skarbce LST użytkowników nie są
+//   dostępne dla tej instrukcji
+// - Wypłaty wstrzymywane pauzą globalną lub pauzą wypłat (PauseTarget::Withdrawals)
 // - Ścisła weryfikacja kont przez Anchor
 
 use anchor_lang::prelude::*;
-use crate::{state::ProgramConfig, errors::ErrorCode};
-
-/// Konta wymagane do aktualizacji programu Sanglass
-#[derive(Accounts)]
-pub struct UpdateSanglassProgram<'info> {
-    #[account(mut, has_one = admin @ ErrorCode::Unauthorized)]
-    pub config: Account<'info, ProgramConfig>,
-    
-    /// Administrator systemu
-    pub admin: Signer<'info>,
-    
-    /// Nowy adres programu Sanglass
-    /// CHECK: Weryfikowany przy użyciu
-    pub new_sanglass_program: AccountInfo<'info>,
-}
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{
+    state::{PauseTarget, ProgramConfig, Role, Roles},
+    errors::ErrorCode
+};
 
 /// Konta wymagane do wypłaty środków
 #[derive(Accounts)]
 pub struct ProcessWithdrawal<'info> {
-    #[account(mut, has_one = admin @ ErrorCode::Unauthorized)]
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Withdrawals) @ ErrorCode::OperationPaused
+    )]
     pub config: Account<'info, ProgramConfig>,
     
-    /// Administrator zatwierdzający wypłatę
-    pub admin: Signer<'info>,
-    
-    /// Konto docelowe w systemie Sanglass
-    /// CHECK: Weryfikowane przez Sanglass
-    #[account(mut)]
-    pub sanglass_destination: AccountInfo<'info>,
-    
-    /// Vault programu (PDA)
     #[account(
-        mut,
-        seeds = [b"vault"],
-        bump,
-        constraint = vault.to_account_info().owner == program_id
+        seeds = [Roles::SEED],
+        bump = roles.bump,
+        constraint = roles.has_role(Role::SanglassOperator, &operator.key()) @ ErrorCode::Unauthorized
     )]
-    pub vault: AccountInfo<'info>,
+    pub roles: Account<'info, Roles>,
+    
+    /// Operator Sanglass zatwierdzający wypłatę (rola sanglass_operator)
+    pub operator: Signer<'info>,
+    
+    /// Konto tokenowe operatora - źródło wypłaty
+    #[account(mut, token::authority = operator)]
+    pub source: Account<'info, TokenAccount>,
+    
+    /// Konto docelowe w systemie Sanglass (weryfikowane przez Sanglass)
+    #[account(mut, token::mint = source.mint)]
+    pub sanglass_destination: Account<'info, TokenAccount>,
     
     /// Program Sanglass
     /// CHECK: Weryfikowany przez config.sanglass_program
+    #[account(address = config.sanglass_program @ ErrorCode::SanglassProgramMismatch)]
     pub sanglass_program: AccountInfo<'info>,
-}
 
-/// Aktualizuje adres programu Sanglass
-pub fn update_sanglass_program_handler(
-    ctx: Context<UpdateSanglassProgram>,
-) -> Result<()> {
-    ctx.accounts.config.sanglass_program = ctx.accounts.new_sanglass_program.key();
-    msg!("Sanglass program updated to: {}", ctx.accounts.config.sanglass_program);
-    Ok(())
+    pub token_program: Program<'info, Token>,
 }
 
 /// Przetwarza wypłatę środków do Sanglass
@@ -69,35 +65,18 @@ pub fn process_withdrawal_handler(
 ) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
     
-    // CPI do Sanglass
-    let transfer_ix = spl_token::instruction::transfer(
-        &spl_token::id(),
-        &ctx.accounts.vault.key(),
-        &ctx.accounts.sanglass_destination.key(),
-        &ctx.accounts.admin.key(),
-        &[],
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source.to_account_info(),
+                to: ctx.accounts.sanglass_destination.to_account_info(),
+                authority: ctx.accounts.operator.to_account_info(),
+            },
+        ),
         amount,
     )?;
     
-    anchor_lang::solana_program::program::invoke(
-        &transfer_ix,
-        &[
-            ctx.accounts.vault.to_account_info(),
-            ctx.accounts.sanglass_destination.to_account_info(),
-            ctx.accounts.admin.to_account_info(),
-        ],
-    )?;
-    
-    msg!("Withdrawal processed to Sanglass: {} lamports", amount);
+    msg!("Withdrawal processed to Sanglass: {} tokens", amount);
     Ok(())
 }
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Unauthorized access")]
-    Unauthorized,
-    #[msg("Invalid amount")]
-    InvalidAmount,
-    #[msg("Sanglass program mismatch")]
-    SanglassProgramMismatch,
-}
\ No newline at end of file
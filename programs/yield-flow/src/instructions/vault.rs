@@ -0,0 +1,243 @@
+// Plik implementujący skarbce (vault) LST programu - jeden na aktywo
+//
+// Główne funkcjonalności:
+// 1. Inicjalizacja skarbca aktywa (admin)
+//    - Tworzy konto stanu Vault (PDA ["vault", mint])
+//    - Tworzy konto tokenowe LST (PDA ["vault-lst", mint]) zarządzane przez Vault
+//
+// 2. Depozyt LST do skarbca
+//    - Przelewa LST z konta użytkownika do vault_lst
+//    - Wymaga włączonego aktywa w rejestrze
+//    - Emituje udziały proporcjonalnie do salda skarbca
+//    - Pierwszy depozyt blokuje LOCKED_VAULT_SHARES udziałów bez właściciela
+//    - Uśrednia kurs bazowy pozycji (base_sol_value)
+//    - Wcześniej rozlicza naliczoną opłatę za zarządzanie
+//
+// Struktury:
+// - InitializeVault: Konta wymagane do utworzenia skarbca
+// - DepositLst: Konta wymagane do dopłaty LST do istniejącej pozycji
+//
+// Bezpieczeństwo:
+// - Udziały liczone wyłącznie z faktycznie przelanego LST
+// - Depozyt bez udziałów dla wpłacającego odrzucany; zablokowane udziały
+//   pierwszego depozytu chronią przed zawyżeniem ceny udziału przelewem
+//   bezpośrednio na konto skarbca
+// - Kurs odczytywany z puli zarejestrowanej dla aktywa pozycji
+// - Konto tokenowe skarbca należy do PDA programu
+// - Zaokrąglenia zawsze na korzyść skarbca
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::{
+    state::{LstAsset, PauseTarget, ProgramConfig, UserStake, Vault},
+    utils::{lst, math},
+    instructions::{claim::pay_protocol_fee, management_fee::charge_management_fee},
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct InitializeVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Vault::LEN,
+        seeds = [Vault::SEED, asset.mint.as_ref()],
+        bump
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [Vault::TOKEN_SEED, asset.mint.as_ref()],
+        bump,
+        token::mint = lst_mint,
+        token::authority = vault
+    )]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    #[account(address = asset.mint @ ErrorCode::InvalidLstMint)]
+    pub lst_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DepositLst<'info> {
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.is_paused(PauseTarget::Deposits) @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(
+        seeds = [LstAsset::SEED, asset.mint.as_ref()],
+        bump = asset.bump,
+        constraint = asset.enabled @ ErrorCode::LstAssetDisabled
+    )]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = user
+    )]
+    pub user_lst: Account<'info, TokenAccount>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn initialize_vault_handler(ctx: Context<InitializeVault>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.mint = ctx.accounts.lst_mint.key();
+    vault.token_account = ctx.accounts.vault_lst.key();
+    vault.total_shares = 0;
+    vault.bump = ctx.bumps.vault;
+    vault.token_bump = ctx.bumps.vault_lst;
+
+    msg!("Vault initialized for {}: {}", vault.mint, vault.token_account);
+    Ok(())
+}
+
+pub fn deposit_handler(ctx: Context<DepositLst>, amount: u64) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+
+    // Opłata za zarządzanie rozliczana przed zmianą kursu bazowego
+    let management = charge_management_fee(
+        &mut ctx.accounts.config,
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        ctx.accounts.vault_lst.amount,
+        current_rate,
+        current_timestamp
+    )?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        management.lst,
+    )?;
+    // Wycena udziałów z salda po przelewie opłaty
+    ctx.accounts.vault_lst.reload()?;
+
+    let shares = transfer_into_vault(
+        &mut ctx.accounts.user_stake,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.user_lst,
+        &ctx.accounts.user,
+        &ctx.accounts.token_program,
+        amount,
+        current_rate,
+    )?;
+
+    msg!(
+        "Deposited {} {} for {} shares (management fee: {})",
+        amount,
+        ctx.accounts.asset.mint,
+        shares,
+        management.lamports
+    );
+    Ok(())
+}
+
+/// Przelewa `amount` LST użytkownika do skarbca i emituje udziały
+///
+/// Zwraca liczbę wyemitowanych udziałów.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_into_vault<'info>(
+    user_stake: &mut Account<'info, UserStake>,
+    vault: &mut Account<'info, Vault>,
+    vault_lst: &Account<'info, TokenAccount>,
+    user_lst: &Account<'info, TokenAccount>,
+    user: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+    current_rate: u64,
+) -> Result<u64> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // Stan skarbca przed przelewem - podstawa wyceny udziałów
+    let vault_balance = vault_lst.amount;
+    let existing_lst = math::shares_to_msol(
+        user_stake.shares,
+        vault.total_shares,
+        vault_balance
+    )?;
+    let (shares, locked_shares) = math::deposit_shares(amount, vault.total_shares, vault_balance)?;
+
+    token::transfer(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Transfer {
+                from: user_lst.to_account_info(),
+                to: vault_lst.to_account_info(),
+                authority: user.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    user_stake.base_sol_value = math::weighted_base_value(
+        existing_lst,
+        user_stake.base_sol_value,
+        amount,
+        current_rate
+    )?;
+    user_stake.shares = user_stake.shares
+        .checked_add(shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+    vault.total_shares = vault.total_shares
+        .checked_add(shares)
+        .and_then(|total| total.checked_add(locked_shares))
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(shares)
+}
@@ -0,0 +1,389 @@
+// Plik implementujący wypłatę kapitału (LST) ze skarbca aktywa
+//
+// Główne funkcjonalności:
+// 1. Rozliczenie opłaty za zarządzanie i niewypłaconej dywidendy przed wypłatą
+//    - Opłata za zarządzanie przelewana na konto LST właściciela opłat
+//    - Dywidenda przeliczana na LST po aktualnym kursie puli aktywa
+//    - Wypłacana razem z kapitałem i zapisywana w historii dywidend
+//    - Opłata protokołu od LST dywidendy (jak w claim.rs - progi FeeTiers
+//      wg wartości pozycji) przelewana na konto LST właściciela opłat
+//    - Kurs bazowy pozycji ustawiany na aktualny
+//
+// 2. Wypłata częściowa lub pełna
+//    - amount < kapitał: spala udziały (zaokrąglenie w górę) i zwraca LST
+//    - amount >= kapitał: spala wszystkie udziały i zwraca całe saldo
+//
+// 3. Wypłata samego kapitału przy pauzie dywidend (Claims lub globalnej)
+//    - Bez opłaty za zarządzanie, dywidendy i opłaty protokołu
+//    - Wypłacane najwyżej LST kapitału - udziały LST zysku zostają w pozycji
+//      do rozliczenia po wznowieniu
+//
+// 4. Zamknięcie pozycji
+//    - Gdy liczba udziałów spada do zera, konto "user-stake" jest zamykane
+//    - Rent wraca do użytkownika
+//
+// Struktury:
+// - WithdrawPrincipal: Konta wymagane do wypłaty
+// - WithdrawalPlan: Rozliczenie wypłaty wyliczane przez plan_withdrawal
+//
+// Bezpieczeństwo:
+// - Wymaga podpisu właściciela pozycji
+// - Wstrzymywana tylko pauzą wypłat (PauseTarget::Withdrawals, bez pauzy
+//   globalnej) - przy pauzie globalnej lub Claims użytkownik zawsze może
+//   odzyskać kapitał (bez rozliczania dywidendy)
+// - Przelew ze skarbca podpisywany przez PDA Vault
+// - Zaokrąglenia zawsze na korzyść skarbca
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::{
+    state::{FeeTiers, LstAsset, PauseTarget, ProgramConfig, UserStake, Vault},
+    utils::{lst, math},
+    instructions::{
+        claim::{pay_protocol_fee, position_value, protocol_fee, record_fee},
+        management_fee::{charge_management_fee, ManagementFee}
+    },
+    errors::ErrorCode
+};
+
+#[derive(Accounts)]
+pub struct WithdrawPrincipal<'info> {
+    #[account(
+        mut,
+        has_one = user,
+        seeds = [b"user-stake", user.key().as_ref(), asset.mint.as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Tylko pauza wypłat - pauza globalna pozwala wypłacić sam kapitał
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::OperationPaused
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(seeds = [FeeTiers::SEED], bump = fee_tiers.bump)]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// Wypłata kapitału działa także dla wyłączonego aktywa
+    #[account(seeds = [LstAsset::SEED, asset.mint.as_ref()], bump = asset.bump)]
+    pub asset: Account<'info, LstAsset>,
+
+    #[account(mut, seeds = [Vault::SEED, asset.mint.as_ref()], bump = vault.bump)]
+    pub vault: Account<'info, Vault>,
+
+    #[account(mut, address = vault.token_account @ ErrorCode::VaultMismatch)]
+    pub vault_lst: Account<'info, TokenAccount>,
+
+    /// Konto LST właściciela opłat (wymagane gdy opłaty są włączone)
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = config.fee_account
+    )]
+    pub fee_lst: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = asset.mint,
+        token::authority = user
+    )]
+    pub user_lst: Account<'info, TokenAccount>,
+
+    /// CHECK: Weryfikowane w lst::read_rate (adres i właściciel z rejestru aktywa)
+    pub pool_state: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<WithdrawPrincipal>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let current_rate = lst::read_rate(&ctx.accounts.config, &ctx.accounts.asset, &ctx.accounts.pool_state)?;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    require!(ctx.accounts.user_stake.shares > 0, ErrorCode::InsufficientShares);
+
+    // Pauza dywidend (lub globalna) - wypłata samego kapitału bez rozliczeń
+    let settle_dividend = !ctx.accounts.config.is_paused(PauseTarget::Claims);
+
+    // Opłata za zarządzanie rozliczana przed wyceną pozycji
+    let management = if settle_dividend {
+        charge_management_fee(
+            &mut ctx.accounts.config,
+            &mut ctx.accounts.user_stake,
+            &mut ctx.accounts.vault,
+            ctx.accounts.vault_lst.amount,
+            current_rate,
+            current_timestamp
+        )?
+    } else {
+        ManagementFee { lamports: 0, lst: 0 }
+    };
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        management.lst,
+    )?;
+
+    let vault_balance = ctx.accounts.vault_lst.amount - management.lst;
+    let total_shares = ctx.accounts.vault.total_shares;
+
+    // 1-2. Rozliczenie dywidendy, kwota wypłaty i udziały do spalenia
+    let plan = plan_withdrawal(
+        &ctx.accounts.user_stake,
+        total_shares,
+        vault_balance,
+        current_rate,
+        amount,
+        settle_dividend
+    )?;
+
+    // Opłata protokołu od LST dywidendy - pokryta spalanymi udziałami
+    let value = position_value(&ctx.accounts.user_stake, total_shares, vault_balance, current_rate)?;
+    let protocol = protocol_fee(
+        &ctx.accounts.config,
+        &ctx.accounts.fee_tiers,
+        value,
+        plan.dividend,
+        plan.yield_lst
+    )?;
+    pay_protocol_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_lst,
+        &ctx.accounts.fee_lst,
+        protocol.lst,
+    )?;
+    record_fee(&mut ctx.accounts.user_stake, &mut ctx.accounts.config, protocol.lamports)?;
+
+    // 3. Przelew LST ze skarbca do użytkownika
+    let asset_mint = ctx.accounts.asset.mint;
+    let vault_seeds: &[&[u8]] = &[Vault::SEED, asset_mint.as_ref(), &[ctx.accounts.vault.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_lst.to_account_info(),
+                to: ctx.accounts.user_lst.to_account_info(),
+                authority: ctx.accounts.vault.to_account_info(),
+            },
+            &[vault_seeds],
+        ),
+        plan.withdraw_lst - protocol.lst,
+    )?;
+
+    // 4. Aktualizacja stanu
+    let user_stake = &mut ctx.accounts.user_stake;
+    user_stake.shares = plan.remaining_shares;
+    ctx.accounts.vault.total_shares = total_shares
+        .checked_sub(plan.burn_shares)
+        .ok_or(ErrorCode::InsufficientShares)?;
+
+    if plan.dividend > 0 {
+        user_stake.base_sol_value = current_rate;
+        user_stake.last_dividend = plan.dividend;
+        user_stake.total_dividends = user_stake.total_dividends
+            .checked_add(plan.dividend)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    msg!(
+        "Withdrawn {} LST (dividend settled: {} lamports, protocol fee: {}, management fee: {}, shares burned: {})",
+        plan.withdraw_lst - protocol.lst,
+        plan.dividend,
+        protocol.lamports,
+        management.lamports,
+        plan.burn_shares
+    );
+
+    // 5. Zamknięcie pozycji po pełnej wypłacie
+    if ctx.accounts.user_stake.shares == 0 {
+        ctx.accounts.user_stake.close(ctx.accounts.user.to_account_info())?;
+        msg!("User stake closed");
+    }
+
+    Ok(())
+}
+
+/// Rozliczenie wypłaty kapitału wyliczone przed przelewem
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct WithdrawalPlan {
+    /// Niewypłacona dywidenda rozliczana przy wypłacie (lamporty)
+    pub dividend: u64,
+    /// LST dywidendy wypłacane razem z kapitałem
+    pub yield_lst: u64,
+    /// Łączna kwota LST przelewana użytkownikowi
+    pub withdraw_lst: u64,
+    /// Udziały do spalenia (nigdy więcej niż udziały pozycji)
+    pub burn_shares: u64,
+    /// Udziały pozostałe po wypłacie (0 = pozycja zamykana)
+    pub remaining_shares: u64,
+}
+
+/// Wylicza wypłatę `amount` LST kapitału (plus niewypłaconej dywidendy)
+///
+/// `amount` >= kapitał pozycji oznacza pełne wyjście - spalane są wszystkie
+/// udziały. Przy wypłacie częściowej udziały spalane z zaokrągleniem w górę.
+/// Bez `settle_dividend` wypłacany jest najwyżej kapitał, a LST zysku
+/// zostaje w pozycji (pełne wyjście tylko gdy zysku nie ma).
+pub(crate) fn plan_withdrawal(
+    user_stake: &UserStake,
+    total_shares: u64,
+    vault_balance: u64,
+    current_rate: u64,
+    amount: u64,
+    settle_dividend: bool
+) -> Result<WithdrawalPlan> {
+    let position_lst = math::shares_to_msol(user_stake.shares, total_shares, vault_balance)?;
+
+    let pending = if current_rate > user_stake.base_sol_value {
+        math::calculate_dividend(position_lst, user_stake.base_sol_value, current_rate)?
+    } else {
+        0
+    };
+    let pending_lst = math::dividend_to_msol(pending, current_rate)?.min(position_lst);
+    let principal_lst = position_lst - pending_lst;
+    let (dividend, yield_lst) = if settle_dividend { (pending, pending_lst) } else { (0, 0) };
+
+    let full_exit = amount >= principal_lst && (settle_dividend || pending_lst == 0);
+    let withdraw_lst = if full_exit {
+        position_lst
+    } else {
+        yield_lst
+            .checked_add(amount.min(principal_lst))
+            .ok_or(ErrorCode::MathOverflow)?
+    };
+    let burn_shares = if full_exit {
+        user_stake.shares
+    } else {
+        math::msol_to_shares_ceil(withdraw_lst, total_shares, vault_balance)?
+            .min(user_stake.shares)
+    };
+
+    Ok(WithdrawalPlan {
+        dividend,
+        yield_lst,
+        withdraw_lst,
+        burn_shares,
+        remaining_shares: user_stake.shares - burn_shares,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u64 = 1_000_000_000;
+
+    fn stake(shares: u64, base_sol_value: u64) -> UserStake {
+        UserStake {
+            shares,
+            base_sol_value,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn partial_withdrawal_burns_proportional_shares() {
+        let plan = plan_withdrawal(&stake(1_000, RATE), 2_000, 2_000, RATE, 400, true).unwrap();
+        assert_eq!(plan.dividend, 0);
+        assert_eq!(plan.withdraw_lst, 400);
+        assert_eq!(plan.burn_shares, 400);
+        assert_eq!(plan.remaining_shares, 600);
+    }
+
+    #[test]
+    fn partial_withdrawal_settles_pending_dividend() {
+        // Kurs wzrósł z 1.0 do 1.1 - 1_000 LST niesie 100 SOL-lamportów zysku (90 LST)
+        let plan = plan_withdrawal(&stake(1_000, RATE), 1_000, 1_000, 1_100_000_000, 400, true).unwrap();
+        assert_eq!(plan.dividend, 100);
+        assert_eq!(plan.yield_lst, 90);
+        assert_eq!(plan.withdraw_lst, 490);
+        assert_eq!(plan.burn_shares, 490);
+        assert_eq!(plan.remaining_shares, 510);
+    }
+
+    #[test]
+    fn full_exit_burns_all_shares() {
+        // Kwota równa kapitałowi (bez dywidendy) to już pełne wyjście
+        let plan = plan_withdrawal(&stake(1_000, 1_100_000_000), 3_000, 3_000, 1_100_000_000, 1_000, true).unwrap();
+        assert_eq!(plan.withdraw_lst, 1_000);
+        assert_eq!(plan.burn_shares, 1_000);
+        assert_eq!(plan.remaining_shares, 0);
+
+        // Kwota ponad kapitał - wypłacane całe saldo pozycji razem z dywidendą
+        let plan = plan_withdrawal(&stake(1_000, RATE), 1_000, 1_000, 1_100_000_000, u64::MAX, true).unwrap();
+        assert_eq!(plan.dividend, 100);
+        assert_eq!(plan.withdraw_lst, 1_000);
+        assert_eq!(plan.remaining_shares, 0);
+    }
+
+    #[test]
+    fn withdrawal_that_reaches_zero_shares_closes_position() {
+        let plan = plan_withdrawal(&stake(7, RATE), 10, 3, RATE, 2, true).unwrap();
+        assert_eq!(plan.withdraw_lst, 2);
+        assert_eq!(plan.burn_shares, 7);
+        assert_eq!(plan.remaining_shares, 0);
+    }
+
+    #[test]
+    fn ceil_burn_never_exceeds_position_shares() {
+        // Nierówny kurs udziałów - zaokrąglenie w górę, ale nie ponad udziały pozycji
+        for total_shares in [7u64, 10, 997, 1_000_003] {
+            for vault_balance in [3u64, 11, 1_000, 999_983] {
+                for shares in [1u64, 2, total_shares / 3, total_shares / 2, total_shares] {
+                    if shares == 0 {
+                        continue;
+                    }
+                    let position_lst = math::shares_to_msol(shares, total_shares, vault_balance).unwrap();
+                    for amount in [1u64, position_lst / 2, position_lst.saturating_sub(1)] {
+                        if amount == 0 {
+                            continue;
+                        }
+                        let plan = plan_withdrawal(
+                            &stake(shares, RATE), total_shares, vault_balance, RATE, amount, true
+                        ).unwrap();
+                        assert!(plan.burn_shares <= shares);
+                        assert_eq!(plan.remaining_shares, shares - plan.burn_shares);
+                        // Spalone udziały pokrywają co najmniej wypłacone LST
+                        assert!(
+                            math::shares_to_msol(plan.burn_shares, total_shares, vault_balance).unwrap()
+                                >= plan.withdraw_lst
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn principal_only_exit_keeps_yield_in_position() {
+        // Pauza dywidend: 90 LST zysku zostaje w pozycji, wypłacany tylko kapitał
+        let plan = plan_withdrawal(&stake(1_000, RATE), 1_000, 1_000, 1_100_000_000, u64::MAX, false).unwrap();
+        assert_eq!(plan.dividend, 0);
+        assert_eq!(plan.yield_lst, 0);
+        assert_eq!(plan.withdraw_lst, 910);
+        assert_eq!(plan.burn_shares, 910);
+        assert_eq!(plan.remaining_shares, 90);
+
+        let plan = plan_withdrawal(&stake(1_000, RATE), 1_000, 1_000, 1_100_000_000, 400, false).unwrap();
+        assert_eq!(plan.withdraw_lst, 400);
+        assert_eq!(plan.remaining_shares, 600);
+    }
+
+    #[test]
+    fn principal_only_exit_without_yield_closes_position() {
+        let plan = plan_withdrawal(&stake(1_000, RATE), 1_000, 1_000, RATE, u64::MAX, false).unwrap();
+        assert_eq!(plan.withdraw_lst, 1_000);
+        assert_eq!(plan.remaining_shares, 0);
+    }
+}
@@ -1,28 +1,58 @@
-// YieldFlow - zarządzanie dywidendami od stakowania mSOL
+// YieldFlow - zarządzanie dywidendami od stakowania LST (mSOL, jitoSOL, bSOL, ...)
 //
 // Główne funkcjonalności:
 // 1. Inicjalizacja stakingu użytkownika (initialize_user_stake)
-// 2. Swap mSOL → USDC (Jupiter)
+//    - LST przechowywane w skarbcu aktywa (vault), użytkownik posiada udziały
+//    - Dopłata LST do pozycji (deposit_lst)
+//    - Wypłata kapitału i zamknięcie pozycji (withdraw_principal)
+// 2. Swap mSOL → USDC (Jupiter, trasa z klienta + weryfikacja sald)
 // 2. Automatyczne i manualne pobieranie dywidend (claim_dividend_auto/claim_dividend_manual)
+//    - Crank auto-wypłat dla keeperów z nagrodą (crank_claim / batch_crank_claim)
+//    - Wypłata w SOL przez Marinade liquid unstake (claim_sol_dividend_auto/manual)
+//    - Wypłata w SOL przez bilety opóźnionego unstake (claim_ticket_dividend_*,
+//      redeem_unstake_ticket)
+//    - Kapitalizacja zysku (compound_dividend) i projekcja sald (project_compound)
 // 3. Funkcje administracyjne:
 //    - Inicjalizacja programu (initialize_program)
-//    - Aktualizacja administratora (update_admin)
+//    - Dwuetapowa zmiana administratora (propose_admin, accept_admin,
+//      cancel_admin_transfer)
+//    - Rejestr aktywów LST (register_lst_asset, set_lst_asset_enabled)
+//    - Lista tokenów wypłaty (add_payout_token, set_payout_token_enabled)
+//    - Opłata protokołu od dywidend (update_program_params) z progami wg
+//      wartości pozycji (initialize_fee_tiers, set_fee_tiers)
+//    - Roczna opłata za zarządzanie (management_fee_bps) i podgląd opłat (preview_fees)
+//    - Role operacyjne (initialize_roles, set_role, revoke_role) - fee_manager,
+//      pauser, registry_manager, sanglass_operator
+//    - Wypłaty do Sanglass (process_withdrawal) - rola sanglass_operator
+//    - Zmiany krytycznych adresów z opóźnieniem (queue_config_change,
+//      execute_config_change, cancel_config_change)
+//    - Pauza awaryjna globalna i per funkcja (set_paused) - przy pauzie
+//      globalnej lub dywidend wypłata samego kapitału dozwolona
 //
 // Struktura modułów:
 // - errors: Definicje błędów programu
 // - instructions: Logika głównych instrukcji
+// - batch: Rozliczenie paczki cranka współdzielone z keeperem
 // - state: Struktury danych programu
 // - utils: Narzędzia pomocnicze
 
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use marinade_finance::State as MarinadeState;
 
 mod errors;
 mod instructions;
-mod state;
-mod utils;
+pub mod state;
+pub mod utils;
+
+/// Rozliczenie pozycji w paczce cranka - czyste funkcje używane także przez keepera
+pub mod batch {
+    pub use crate::instructions::{
+        claim::pending_dividend,
+        crank::{batch_position_status, settle_batch_position, BatchSettlement, CrankClaimStatus},
+    };
+}
+
+use instructions::*;
 
 declare_id!("D2yN7v2dAhXEyFojzWMH6HxXRxzyGmje7S1Rs9HiQc8Q");
 
@@ -37,33 +67,144 @@ pub mod yieldflow {
         ctx: Context<DepositSol>,
         amount_lamports: u64,
     ) -> Result<()> {
-        instructions::marinade::deposit_handler(ctx, amount_lamports)
+        instructions::deposit_sol::handler(ctx, amount_lamports)
     }
 
-    /// Wymiana mSOL -> USDC przez Jupiter
-    pub fn swap_msol_to_usdc(
-        ctx: Context<SwapMsolToUsdc>,
-        amount: u64,
+    /// Wymiana mSOL -> USDC przez Jupiter (trasa z klienta, podłoga z wyroczni Pyth)
+    pub fn swap_msol_to_usdc<'info>(
+        ctx: Context<'_, '_, '_, 'info, SwapMsolToUsdc<'info>>,
+        route_data: Vec<u8>,
+        amount_in: u64,
         slippage_bps: u16,
     ) -> Result<()> {
-        instructions::jupiter::swap_handler(ctx, amount, slippage_bps)
+        instructions::swap::handler(ctx, route_data, amount_in, slippage_bps)
     }
 
     // ========== ZARZĄDZANIE DYWDENDAMI ========== //
 
+    /// Tworzy pozycję użytkownika dla aktywa i wpłaca początkowe LST do skarbca
     pub fn initialize_user_stake(
         ctx: Context<InitializeUserStake>,
-        msol_amount: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::initialize::handler(ctx, amount)
+    }
+
+    /// Dopłata LST do istniejącej pozycji (emisja nowych udziałów)
+    pub fn deposit_lst(
+        ctx: Context<DepositLst>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::vault::deposit_handler(ctx, amount)
+    }
+
+    /// Wypłata kapitału LST (rozlicza dywidendę, zamyka pozycję przy zerowym saldzie)
+    pub fn withdraw_principal(
+        ctx: Context<WithdrawPrincipal>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw::handler(ctx, amount)
+    }
+
+    /// Ustawia harmonogram wypłat (godzina lokalna + przesunięcie UTC dla Weekly/Monthly)
+    pub fn set_schedule(
+        ctx: Context<SetSchedule>,
+        params: SetScheduleParams,
+    ) -> Result<()> {
+        instructions::set_schedule::handler(ctx, params)
+    }
+
+    /// Wybór tokenu wypłaty (USDC, USDT, PYUSD, wSOL, ...) z listy dozwolonych
+    pub fn set_payout_token(ctx: Context<SetPayoutToken>) -> Result<()> {
+        instructions::payout_token::handler(ctx)
+    }
+
+    /// Ustawia beneficjentów wypłaty (udziały w bps, suma = 100%; pusta lista = właściciel)
+    pub fn set_beneficiaries(
+        ctx: Context<SetBeneficiaries>,
+        beneficiaries: Vec<state::Beneficiary>,
+    ) -> Result<()> {
+        instructions::beneficiaries::handler(ctx, beneficiaries)
+    }
+
+    /// Wypłata dywidendy wg harmonogramu - LST zysku sprzedawane przez Jupiter
+    pub fn claim_dividend_auto<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimDividend<'info>>,
+        route_data: Vec<u8>,
+        slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::claim::handler(ctx, ClaimMode::Auto, route_data, slippage_bps)
+    }
+
+    /// Ręczna wypłata dywidendy - LST zysku sprzedawane przez Jupiter
+    pub fn claim_dividend_manual<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimDividend<'info>>,
+        route_data: Vec<u8>,
+        slippage_bps: u16,
     ) -> Result<()> {
-        instructions::dividend::initialize_handler(ctx, msol_amount)
+        instructions::claim::handler(ctx, ClaimMode::Manual, route_data, slippage_bps)
+    }
+
+    /// Kapitalizacja dywidendy (podpis właściciela) - zysk zostaje w skarbcu, kurs bazowy pozycji rośnie
+    pub fn compound_dividend(ctx: Context<CompoundDividend>) -> Result<()> {
+        instructions::compound::compound_handler(ctx)
+    }
+
+    /// Projekcja salda pozycji po kolejnych okresach kapitalizacji (widok)
+    pub fn project_compound(
+        ctx: Context<ProjectCompound>,
+        periods: u64,
+        rate_per_period_bps: u64,
+    ) -> Result<CompoundProjection> {
+        instructions::compound::projection_handler(ctx, periods, rate_per_period_bps)
+    }
+
+    /// Podgląd naliczonej opłaty za zarządzanie i opłaty protokołu od dywidendy (widok)
+    pub fn preview_fees(ctx: Context<PreviewFees>) -> Result<FeePreview> {
+        instructions::management_fee::preview_handler(ctx)
+    }
+
+    /// Wypłata dywidendy wg harmonogramu w SOL (Marinade liquid unstake)
+    pub fn claim_sol_dividend_auto(ctx: Context<ClaimDividendSol>) -> Result<()> {
+        instructions::claim_sol::handler(ctx, ClaimMode::Auto)
+    }
+
+    /// Ręczna wypłata dywidendy w SOL (Marinade liquid unstake)
+    pub fn claim_sol_dividend_manual(ctx: Context<ClaimDividendSol>) -> Result<()> {
+        instructions::claim_sol::handler(ctx, ClaimMode::Manual)
+    }
+
+    /// Wypłata dywidendy wg harmonogramu jako bilet opóźnionego unstake Marinade
+    pub fn claim_ticket_dividend_auto(ctx: Context<ClaimDividendTicket>) -> Result<()> {
+        instructions::unstake_ticket::order_handler(ctx, ClaimMode::Auto)
+    }
+
+    /// Ręczna wypłata dywidendy jako bilet opóźnionego unstake Marinade
+    pub fn claim_ticket_dividend_manual(ctx: Context<ClaimDividendTicket>) -> Result<()> {
+        instructions::unstake_ticket::order_handler(ctx, ClaimMode::Manual)
+    }
+
+    /// Odbiór biletu unstake po zakończeniu epoki - SOL trafia do właściciela
+    pub fn redeem_unstake_ticket(ctx: Context<RedeemUnstakeTicket>) -> Result<()> {
+        instructions::unstake_ticket::redeem_handler(ctx)
     }
 
-    pub fn claim_dividend_auto(ctx: Context<ClaimDividend>) -> Result<()> {
-        instructions::dividend::claim_handler(ctx, state::ClaimMode::Auto)
+    /// Auto-wypłata wywoływana przez dowolnego keepera (nagroda w bps wypłaty)
+    pub fn crank_claim<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankClaim<'info>>,
+        route_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::crank::handler(ctx, route_data)
     }
 
-    pub fn claim_dividend_manual(ctx: Context<ClaimDividend>) -> Result<()> {
-        instructions::dividend::claim_handler(ctx, state::ClaimMode::Manual)
+    /// Wsadowy crank auto-wypłat - `position_count` grup [user_stake, owner_payout,
+    /// konta beneficjentów...], a po nich konta trasy Jupitera w remaining_accounts
+    pub fn batch_crank_claim<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchCrankClaim<'info>>,
+        position_count: u8,
+        route_data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::crank::batch_handler(ctx, position_count, route_data)
     }
 
     // ========== ADMINISTRACJA ========== //
@@ -72,76 +213,153 @@ pub mod yieldflow {
         ctx: Context<InitializeProgram>,
         params: state::ProgramParams,
     ) -> Result<()> {
-        instructions::admin::initialize_handler(ctx, params)
+        instructions::admin::initialize_program_handler(ctx, params)
+    }
+
+    /// Tworzy skarbiec zarejestrowanego aktywa LST (jednorazowo na aktywo)
+    pub fn initialize_vault(ctx: Context<InitializeVault>) -> Result<()> {
+        instructions::vault::initialize_vault_handler(ctx)
+    }
+
+    /// Rejestruje aktywo LST (mint, czytnik kursu, pula, wyrocznia LST/SOL) - tylko admin
+    pub fn register_lst_asset(
+        ctx: Context<RegisterLstAsset>,
+        rate_reader: state::RateReader,
+        pool_program: Pubkey,
+    ) -> Result<()> {
+        instructions::admin::register_lst_asset_handler(ctx, rate_reader, pool_program)
+    }
+
+    /// Włącza/wyłącza nowe depozyty aktywa (wypłaty kapitału zawsze dozwolone)
+    pub fn set_lst_asset_enabled(
+        ctx: Context<SetLstAssetEnabled>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::admin::set_lst_asset_enabled_handler(ctx, enabled)
+    }
+
+    /// Dodaje token do listy dozwolonych tokenów wypłaty
+    pub fn add_payout_token(
+        ctx: Context<AddPayoutToken>,
+        quote: state::PayoutQuote,
+    ) -> Result<()> {
+        instructions::admin::add_payout_token_handler(ctx, quote)
+    }
+
+    /// Włącza/wyłącza token wypłaty (wyłączony nie może być wybrany ani wypłacany)
+    pub fn set_payout_token_enabled(
+        ctx: Context<SetPayoutTokenEnabled>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::admin::set_payout_token_enabled_handler(ctx, enabled)
+    }
+
+    /// Proponuje nowego administratora (przejęcie wymaga jego podpisu w accept_admin)
+    pub fn propose_admin(ctx: Context<ProposeAdmin>) -> Result<()> {
+        instructions::admin::propose_admin_handler(ctx)
+    }
+
+    /// Przejęcie uprawnień przez zaproponowanego administratora
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::admin::accept_admin_handler(ctx)
+    }
+
+    /// Anuluje propozycję zmiany administratora przed akceptacją
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        instructions::admin::cancel_admin_transfer_handler(ctx)
+    }
+
+    /// Wstrzymuje / wznawia operacje (globalnie lub grupę) - rola pauser;
+    /// wypłatę kapitału blokuje tylko pauza Withdrawals
+    pub fn set_paused(
+        ctx: Context<SetPaused>,
+        target: state::PauseTarget,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::admin::set_paused_handler(ctx, target, paused)
+    }
+
+    pub fn set_keeper_bounty(
+        ctx: Context<SetKeeperBounty>,
+        bounty_bps: u16,
+    ) -> Result<()> {
+        instructions::admin::set_keeper_bounty_handler(ctx, bounty_bps)
+    }
+
+    /// Wyrocznie Pyth i ich limity (slippage <= MAX_ORACLE_SLIPPAGE_BPS) - tylko admin;
+    /// adresy wyroczni ustawiane tu tylko przy pierwszej konfiguracji, później przez timelock
+    pub fn set_oracles(
+        ctx: Context<SetOracles>,
+        max_age_secs: u64,
+        max_conf_bps: u16,
+        max_slippage_bps: u16,
+    ) -> Result<()> {
+        instructions::admin::set_oracles_handler(ctx, max_age_secs, max_conf_bps, max_slippage_bps)
+    }
+
+    /// Tworzy pustą tabelę progów opłaty (programy zainicjalizowane bez tabeli;
+    /// initialize_program tworzy ją od razu)
+    pub fn initialize_fee_tiers(ctx: Context<InitializeFeeTiers>) -> Result<()> {
+        instructions::admin::initialize_fee_tiers_handler(ctx)
     }
 
-    pub fn update_admin(ctx: Context<UpdateAdmin>) -> Result<()> {
-        instructions::admin::update_admin_handler(ctx)
+    /// Progi opłaty wg wartości pozycji w lamportach (rosnąco, pierwszy od 0)
+    pub fn set_fee_tiers(
+        ctx: Context<SetFeeTiers>,
+        tiers: Vec<state::FeeTier>,
+    ) -> Result<()> {
+        instructions::admin::set_fee_tiers_handler(ctx, tiers)
     }
 
+    /// Parametry opłat (fees_enabled, fee_rate <= MAX_BPS, management_fee_bps) - rola fee_manager
     pub fn update_program_params(
         ctx: Context<UpdateProgramParams>,
         new_params: state::ProgramParams,
     ) -> Result<()> {
         instructions::admin::update_params_handler(ctx, new_params)
     }
-}
 
-// ========== KONTEKSTY DLA INSTRUKCJI ========== //
-
-#[derive(Accounts)]
-pub struct DepositSol<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(mut)]
-    pub user_sol: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub user_msol: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub marinade_state: Account<'info, MarinadeState>,
-    
-    #[account(mut)]
-    pub msol_mint: Account<'info, Mint>,
-    
-    // Marinade liquidity pool accounts
-    #[account(mut)]
-    pub liq_pool_sol: AccountInfo<'info>,
-    #[account(mut)]
-    pub liq_pool_msol: Account<'info, TokenAccount>,
-    pub liq_pool_authority: AccountInfo<'info>,
-    
-    #[account(mut)]
-    pub reserve_pda: AccountInfo<'info>,
-    
-    pub marinade_program: Program<'info, marinade_finance::program::MarinadeFinance>,
-    pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-}
+    /// Kolejkuje zmianę krytycznego adresu (marinade_program, usdc_mint, fee_account,
+    /// sanglass_program, wyrocznie) - wykonanie możliwe po CONFIG_TIMELOCK_SECS
+    pub fn queue_config_change(
+        ctx: Context<QueueConfigChange>,
+        field: state::ConfigField,
+        new_value: Pubkey,
+    ) -> Result<()> {
+        instructions::timelock::queue_handler(ctx, field, new_value)
+    }
 
-#[derive(Accounts)]
-pub struct SwapMsolToUsdc<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    #[account(mut)]
-    pub user_msol: Account<'info, TokenAccount>,
-    
-    #[account(mut)]
-    pub user_usdc: Account<'info, TokenAccount>,
-    
-    // Jupiter swap accounts
-    #[account(mut)]
-    pub jupiter_router: AccountInfo<'info>,
-    pub jupiter_program: Program<'info, jupiter_amm::program::Jupiter>,
-    
-    // Token accounts
-    pub msol_mint: Account<'info, Mint>,
-    pub usdc_mint: Account<'info, Mint>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    /// Wykonuje zakolejkowaną zmianę po upływie timelocka (dowolny podpisujący)
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::timelock::execute_handler(ctx)
+    }
+
+    /// Anuluje zakolejkowaną zmianę przed wykonaniem
+    pub fn cancel_config_change(ctx: Context<CancelConfigChange>) -> Result<()> {
+        instructions::timelock::cancel_handler(ctx)
+    }
+
+    /// Przelew tokenów operatora do systemu Sanglass - rola sanglass_operator
+    pub fn process_withdrawal(ctx: Context<ProcessWithdrawal>, amount: u64) -> Result<()> {
+        instructions::update_config::process_withdrawal_handler(ctx, amount)
+    }
+
+    /// Tworzy tabelę ról operacyjnych (wszystkie role początkowo u admina)
+    pub fn initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+        instructions::roles::initialize_roles_handler(ctx)
+    }
+
+    /// Nadaje rolę operacyjną (fee_manager, pauser, registry_manager, sanglass_operator)
+    pub fn set_role(
+        ctx: Context<UpdateRole>,
+        role: state::Role,
+        holder: Pubkey,
+    ) -> Result<()> {
+        instructions::roles::set_role_handler(ctx, role, holder)
+    }
 
+    /// Odbiera rolę operacyjną (do czasu ponownego nadania nikt jej nie posiada)
+    pub fn revoke_role(ctx: Context<UpdateRole>, role: state::Role) -> Result<()> {
+        instructions::roles::revoke_role_handler(ctx, role)
+    }
+}
@@ -6,13 +6,36 @@
 // - msol_mint: Pubkey - adres mint'a mSOL
 // - sanglass_program: Pubkey - adres programu Sanglass
 // - usdc_mint: Pubkey - adres mint'a USDC
-// - fee_account: Pubkey - konto na które trafiają opłaty
+// - fee_account: Pubkey - właściciel kont opłat (opłata w LST trafia na jego konto
+//   tokenu danego aktywa)
 // - bump: u8 - wartość bump dla PDA
 // - fees_enabled: bool - czy opłaty są aktywne
-// - fee_rate: u16 - procentowa stawka opłaty (np. 100 = 1%)
+// - fee_rate: u16 - procentowa stawka opłaty (np. 100 = 1%) od wypłacanej dywidendy
+// - keeper_bounty_bps: u16 - nagroda keepera za crank auto-wypłaty (bps dywidendy)
+// - sol_usd_oracle: Pubkey - konto ceny Pyth SOL/USD
+// - msol_sol_oracle: Pubkey - konto ceny Pyth mSOL/SOL
+// - oracle_max_age_secs: u64 - maksymalny wiek ceny z wyroczni
+// - oracle_max_conf_bps: u16 - maksymalny przedział ufności (bps ceny)
+// - oracle_max_slippage_bps: u16 - tolerancja swapów keepera względem ceny z wyroczni
+// - total_fees_collected: u64 - łączne opłaty protokołu w lamportach (wszystkie aktywa,
+//   opłata od dywidend + opłata za zarządzanie)
+// - management_fee_bps: u16 - roczna opłata za zarządzanie od kapitału w SOL
+//   (naliczana co sekundę od UserStake.last_update)
+// - pending_admin: Option<Pubkey> - zaproponowany nowy administrator (czeka na akceptację)
+// - paused: bool - globalne wstrzymanie wszystkich operacji objętych pauzą
+// - deposits_paused / swaps_paused / claims_paused / withdrawals_paused: bool -
+//   wstrzymanie pojedynczych grup operacji (przy pauzie globalnej lub dywidend
+//   wypłata samego kapitału pozostaje dostępna)
 //
 // - impl ProgramConfig - zawiera stałą LEN określającą rozmiar struktury
-//   (32 bajty * 5 pól Pubkey + 1 bajt bump + 1 bajt bool + 2 bajty fee_rate)
+//   (8 bajtów dyskryminatora + 32 bajty * 8 pól Pubkey + 1 bajt bump
+//    + 1 bajt bool + 2 bajty fee_rate + 2 bajty keeper_bounty_bps
+//    + 8 bajtów oracle_max_age_secs + 2 bajty oracle_max_conf_bps
+//    + 2 bajty oracle_max_slippage_bps + 8 bajtów total_fees_collected
+//    + 2 bajty management_fee_bps + 33 bajty pending_admin + 5 bajtów flag pauzy)
+//
+// - ProgramParams - parametry opłat ustawiane przez update_program_params
+// - PauseTarget - grupa operacji wstrzymywana przez set_paused
 
 use anchor_lang::prelude::*;
 
@@ -27,8 +50,59 @@ pub struct ProgramConfig {
     pub bump: u8,
     pub fees_enabled: bool,
     pub fee_rate: u16,
+    pub keeper_bounty_bps: u16,
+    pub sol_usd_oracle: Pubkey,
+    pub msol_sol_oracle: Pubkey,
+    pub oracle_max_age_secs: u64,
+    pub oracle_max_conf_bps: u16,
+    pub oracle_max_slippage_bps: u16,
+    pub total_fees_collected: u64,
+    pub management_fee_bps: u16,
+    pub pending_admin: Option<Pubkey>,
+    pub paused: bool,
+    pub deposits_paused: bool,
+    pub swaps_paused: bool,
+    pub claims_paused: bool,
+    pub withdrawals_paused: bool,
 }
 
 impl ProgramConfig {
-    pub const LEN: usize = 32 * 5 + 1 + 1 + 2;  
-}
\ No newline at end of file
+    pub const LEN: usize = 8 + 32 * 8 + 1 + 1 + 2 + 2 + 8 + 2 + 2 + 8 + 2 + 1 + 32 + 5;
+
+    /// Czy operacje z grupy `target` są wstrzymane (pauza globalna obejmuje wszystkie)
+    pub fn is_paused(&self, target: PauseTarget) -> bool {
+        self.paused || match target {
+            PauseTarget::All => false,
+            PauseTarget::Deposits => self.deposits_paused,
+            PauseTarget::Swaps => self.swaps_paused,
+            PauseTarget::Claims => self.claims_paused,
+            PauseTarget::Withdrawals => self.withdrawals_paused,
+        }
+    }
+
+    pub fn set_paused(&mut self, target: PauseTarget, paused: bool) {
+        match target {
+            PauseTarget::All => self.paused = paused,
+            PauseTarget::Deposits => self.deposits_paused = paused,
+            PauseTarget::Swaps => self.swaps_paused = paused,
+            PauseTarget::Claims => self.claims_paused = paused,
+            PauseTarget::Withdrawals => self.withdrawals_paused = paused,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProgramParams {
+    pub fees_enabled: bool,
+    pub fee_rate: u16,
+    pub management_fee_bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PauseTarget {
+    All,         // Globalny wyłącznik
+    Deposits,    // Nowe pozycje i dopłaty (LST i SOL)
+    Swaps,       // Swapy przez Jupiter (w tym wypłaty dywidend w tokenie)
+    Claims,      // Wypłaty i kapitalizacja dywidend, cranki keeperów
+    Withdrawals, // Wypłaty kapitału i wypłaty do Sanglass
+}
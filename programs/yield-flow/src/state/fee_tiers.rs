@@ -0,0 +1,90 @@
+// Tabela progów opłaty protokołu (rabat wolumenowy)
+//
+// Pola:
+// - tiers: Vec<FeeTier> - progi posortowane rosnąco wg min_value
+//   (max MAX_FEE_TIERS, pierwszy próg od 0)
+//   * min_value: u64 - minimalna wartość pozycji w lamportach SOL
+//   * fee_bps: u16 - opłata dla pozycji od tej wartości
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["fee-tiers"] - jedna tabela na program, tworzona (pusta)
+//   w initialize_program
+//
+// Wybór progu:
+// - najwyższy próg z min_value <= aktualnej wartości pozycji
+// - pusta tabela = stała stawka fee_rate z ProgramConfig
+//
+// - impl FeeTiers - zawiera stałe LEN i seed PDA oraz wybór progu
+
+use anchor_lang::prelude::*;
+use crate::utils::constants::MAX_FEE_TIERS;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeTier {
+    pub min_value: u64,
+    pub fee_bps: u16,
+}
+
+#[account]
+pub struct FeeTiers {
+    pub tiers: Vec<FeeTier>,
+    pub bump: u8,
+}
+
+impl FeeTiers {
+    pub const SEED: &'static [u8] = b"fee-tiers";
+
+    // 8 bajtów dyskryminatora + Vec<FeeTier> (4 + MAX_FEE_TIERS * (8 + 2)) + bump
+    pub const LEN: usize = 8 + 4 + MAX_FEE_TIERS * (8 + 2) + 1;
+
+    /// Indeks i stawka progu dla pozycji o wartości `position_value` (lamporty)
+    pub fn tier_for(&self, position_value: u64) -> Option<(u8, u16)> {
+        self.tiers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, tier)| tier.min_value <= position_value)
+            .map(|(index, tier)| (index as u8, tier.fee_bps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(tiers: &[(u64, u16)]) -> FeeTiers {
+        FeeTiers {
+            tiers: tiers
+                .iter()
+                .map(|&(min_value, fee_bps)| FeeTier { min_value, fee_bps })
+                .collect(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn empty_table_has_no_tier() {
+        let fee_tiers = table(&[]);
+        assert_eq!(fee_tiers.tier_for(0), None);
+        assert_eq!(fee_tiers.tier_for(u64::MAX), None);
+    }
+
+    #[test]
+    fn tier_starts_at_its_min_value() {
+        let fee_tiers = table(&[(0, 1_000), (100_000_000_000, 500), (1_000_000_000_000, 250)]);
+        assert_eq!(fee_tiers.tier_for(0), Some((0, 1_000)));
+        assert_eq!(fee_tiers.tier_for(99_999_999_999), Some((0, 1_000)));
+        assert_eq!(fee_tiers.tier_for(100_000_000_000), Some((1, 500)));
+        assert_eq!(fee_tiers.tier_for(999_999_999_999), Some((1, 500)));
+        assert_eq!(fee_tiers.tier_for(1_000_000_000_000), Some((2, 250)));
+        assert_eq!(fee_tiers.tier_for(u64::MAX), Some((2, 250)));
+    }
+
+    #[test]
+    fn single_tier_covers_every_value() {
+        let fee_tiers = table(&[(0, 700)]);
+        assert_eq!(fee_tiers.tier_for(0), Some((0, 700)));
+        assert_eq!(fee_tiers.tier_for(u64::MAX), Some((0, 700)));
+    }
+}
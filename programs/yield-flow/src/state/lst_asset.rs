@@ -0,0 +1,42 @@
+// Rejestr aktywów LST (mSOL, jitoSOL, bSOL, ...) obsługiwanych przez program
+//
+// Pola:
+// - mint: Pubkey - mint tokenu LST
+// - rate_reader: RateReader - sposób odczytu kursu z konta puli
+// - pool_program: Pubkey - program puli (Marinade / SPL Stake Pool)
+// - pool_state: Pubkey - konto stanu puli (State Marinade / StakePool)
+// - lst_sol_oracle: Pubkey - konto ceny Pyth LST/SOL (podłoga swapów)
+// - enabled: bool - czy można otwierać nowe pozycje i wpłacać
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["lst-asset", mint]
+// - Skarbiec aktywa: Vault PDA ["vault", mint], konto tokenowe ["vault-lst", mint]
+//
+// - impl LstAsset - zawiera stałe LEN i seed PDA
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RateReader {
+    Marinade,     // State.msol_price
+    SplStakePool, // StakePool.total_lamports / pool_token_supply
+}
+
+#[account]
+pub struct LstAsset {
+    pub mint: Pubkey,
+    pub rate_reader: RateReader,
+    pub pool_program: Pubkey,
+    pub pool_state: Pubkey,
+    pub lst_sol_oracle: Pubkey,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl LstAsset {
+    pub const SEED: &'static [u8] = b"lst-asset";
+
+    // 8 bajtów dyskryminatora + 4 * Pubkey + RateReader + bool + bump
+    pub const LEN: usize = 8 + 32 * 4 + 1 + 1 + 1;
+}
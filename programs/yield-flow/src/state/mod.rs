@@ -2,15 +2,43 @@
 // Zawiera:
 // - config - moduł z konfiguracją programu (ProgramConfig)
 // - user_stake - moduł ze strukturami związanymi ze stakingiem użytkowników
+// - vault - moduł ze stanem skarbca LST (Vault)
+// - lst_asset - moduł z rejestrem aktywów LST (LstAsset)
+// - payout_token - moduł z listą dozwolonych tokenów wypłaty (PayoutToken)
+// - unstake_ticket - moduł z biletami opóźnionego unstake (UnstakeTicket)
+// - fee_tiers - moduł z tabelą progów opłaty protokołu (FeeTiers)
+// - roles - moduł z rolami operacyjnymi programu (Roles)
+// - pending_change - moduł ze zmianami konfiguracji czekającymi na timelock (PendingChange)
 //
 // Udostępnia publicznie:
 // - wszystkie struktury z modułu config
 // - wszystkie struktury z modułu user_stake
+// - wszystkie struktury z modułu vault
+// - wszystkie struktury z modułu lst_asset
+// - wszystkie struktury z modułu payout_token
+// - wszystkie struktury z modułu unstake_ticket
+// - wszystkie struktury z modułu fee_tiers
+// - wszystkie struktury z modułu roles
+// - wszystkie struktury z modułu pending_change
 //
 // Służy jako główny punkt eksportu struktur stanu programu
 
 pub mod config;
 pub mod user_stake;
+pub mod vault;
+pub mod lst_asset;
+pub mod payout_token;
+pub mod unstake_ticket;
+pub mod fee_tiers;
+pub mod roles;
+pub mod pending_change;
 
 pub use config::*;
-pub use user_stake::*;
\ No newline at end of file
+pub use user_stake::*;
+pub use vault::*;
+pub use lst_asset::*;
+pub use payout_token::*;
+pub use unstake_ticket::*;
+pub use fee_tiers::*;
+pub use roles::*;
+pub use pending_change::*;
\ No newline at end of file
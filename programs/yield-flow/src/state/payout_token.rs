@@ -0,0 +1,36 @@
+// Lista dozwolonych tokenów wypłaty dywidend (USDC, USDT, PYUSD, wSOL, ...)
+//
+// Pola:
+// - mint: Pubkey - mint tokenu wypłaty (wyjście swapu Jupitera)
+// - quote: PayoutQuote - w czym wyceniany jest token (podłoga z wyroczni)
+// - enabled: bool - czy token może być wybrany i wypłacany
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["payout-token", mint]
+// - Konto skarbca na token wypłaty: dowolne konto tokenowe z authority = Vault
+//
+// - impl PayoutToken - zawiera stałe LEN i seed PDA
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PayoutQuote {
+    Usd, // Stablecoin 1:1 z USD - podłoga z SOL/USD * LST/SOL
+    Sol, // wSOL - podłoga z LST/SOL
+}
+
+#[account]
+pub struct PayoutToken {
+    pub mint: Pubkey,
+    pub quote: PayoutQuote,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl PayoutToken {
+    pub const SEED: &'static [u8] = b"payout-token";
+
+    // 8 bajtów dyskryminatora + Pubkey + PayoutQuote + bool + bump
+    pub const LEN: usize = 8 + 32 + 1 + 1 + 1;
+}
@@ -0,0 +1,44 @@
+// Zakolejkowana zmiana krytycznego adresu konfiguracji (timelock)
+//
+// Pola:
+// - field: ConfigField - zmieniane pole ProgramConfig
+// - new_value: Pubkey - nowy adres
+// - proposer: Pubkey - admin, który zakolejkował zmianę (odbiorca rentu)
+// - queued_at: i64 - timestamp zakolejkowania
+// - execute_after: i64 - timestamp, od którego zmianę może wykonać każdy
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["pending-change", field (u8)] - co najwyżej jedna oczekująca
+//   zmiana na pole
+//
+// - impl PendingChange - zawiera stałe LEN i seed PDA
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ConfigField {
+    MarinadeProgram,
+    UsdcMint,
+    FeeAccount,
+    SanglassProgram,
+    SolUsdOracle,
+    MsolSolOracle,
+}
+
+#[account]
+pub struct PendingChange {
+    pub field: ConfigField,
+    pub new_value: Pubkey,
+    pub proposer: Pubkey,
+    pub queued_at: i64,
+    pub execute_after: i64,
+    pub bump: u8,
+}
+
+impl PendingChange {
+    pub const SEED: &'static [u8] = b"pending-change";
+
+    // 8 bajtów dyskryminatora + ConfigField + 2 * Pubkey + 2 * i64 + bump
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 * 2 + 1;
+}
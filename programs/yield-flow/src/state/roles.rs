@@ -0,0 +1,69 @@
+// Role operacyjne programu (kontrola dostępu poza kluczem admina)
+//
+// Pola:
+// - fee_manager: Pubkey - parametry opłat, tabela progów, nagroda keepera
+// - pauser: Pubkey - wstrzymywanie operacji programu
+// - registry_manager: Pubkey - dostępność aktywów LST, lista tokenów wypłaty
+// - sanglass_operator: Pubkey - wypłaty do Sanglass
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["roles"] - jedna tabela ról na program
+//
+// Nadawanie ról:
+// - Tylko admin (ProgramConfig.admin) nadaje i odbiera role
+// - Odebrana rola = Pubkey::default() (nikt nie może jej użyć)
+// - Zmiana krytycznych adresów i administratora pozostaje przy adminie
+//
+// - impl Roles - zawiera stałe LEN i seed PDA oraz odczyt / zapis roli
+
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Role {
+    FeeManager,
+    Pauser,
+    RegistryManager,
+    SanglassOperator,
+}
+
+#[account]
+pub struct Roles {
+    pub fee_manager: Pubkey,
+    pub pauser: Pubkey,
+    pub registry_manager: Pubkey,
+    pub sanglass_operator: Pubkey,
+    pub bump: u8,
+}
+
+impl Roles {
+    pub const SEED: &'static [u8] = b"roles";
+
+    // 8 bajtów dyskryminatora + 4 * Pubkey + bump
+    pub const LEN: usize = 8 + 32 * 4 + 1;
+
+    /// Aktualny posiadacz roli (Pubkey::default() = rola odebrana)
+    pub fn holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::FeeManager => self.fee_manager,
+            Role::Pauser => self.pauser,
+            Role::RegistryManager => self.registry_manager,
+            Role::SanglassOperator => self.sanglass_operator,
+        }
+    }
+
+    pub fn set_holder(&mut self, role: Role, holder: Pubkey) {
+        match role {
+            Role::FeeManager => self.fee_manager = holder,
+            Role::Pauser => self.pauser = holder,
+            Role::RegistryManager => self.registry_manager = holder,
+            Role::SanglassOperator => self.sanglass_operator = holder,
+        }
+    }
+
+    /// Czy `key` posiada rolę (odebrana rola nie pasuje do żadnego podpisu)
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        let holder = self.holder(role);
+        holder != Pubkey::default() && holder == *key
+    }
+}
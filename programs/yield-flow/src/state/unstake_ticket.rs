@@ -0,0 +1,106 @@
+// Bilet opóźnionego unstake (Marinade order_unstake) przypisany do pozycji
+//
+// Pola:
+// - user: Pubkey - właściciel pozycji, odbiorca SOL
+// - user_stake: Pubkey - pozycja, z której pochodzi mSOL zysku
+// - marinade_ticket: Pubkey - konto biletu Marinade (PDA "marinade-ticket")
+// - msol_amount: u64 - mSOL zysku przekazane do unstake
+// - lamports_amount: u64 - wartość mSOL w SOL w chwili zlecenia
+// - dividend: u64 - rozliczona dywidenda w lamportach
+// - created_epoch: u64 - epoka zlecenia (odbiór możliwy w kolejnej)
+// - bump: u8 - wartość bump dla PDA
+//
+// Adresy:
+// - PDA ["unstake-ticket", user_stake, ticket_id (u64 LE)], gdzie ticket_id
+//   to UserStake.ticket_count w chwili zlecenia
+// - Lista biletów użytkownika: getProgramAccounts z filtrem memcmp na polu
+//   user (offset 8) i rozmiarem UnstakeTicket::LEN
+//
+// Gotowość odbioru (odwzorowanie warunku claim Marinade):
+// - najwcześniej w epoce created_epoch + 1
+// - w tej epoce dopiero EXTRA_WAIT_SECS po jej rozpoczęciu
+//
+// - impl UnstakeTicket - zawiera stałe LEN i seedy PDA oraz is_ready
+
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct UnstakeTicket {
+    pub user: Pubkey,
+    pub user_stake: Pubkey,
+    pub marinade_ticket: Pubkey,
+    pub msol_amount: u64,
+    pub lamports_amount: u64,
+    pub dividend: u64,
+    pub created_epoch: u64,
+    pub bump: u8,
+}
+
+impl UnstakeTicket {
+    pub const SEED: &'static [u8] = b"unstake-ticket";
+    pub const MARINADE_SEED: &'static [u8] = b"marinade-ticket";
+
+    // 8 bajtów dyskryminatora + 3 * Pubkey + 4 * u64 + bump
+    pub const LEN: usize = 8 + 32 * 3 + 8 * 4 + 1;
+
+    /// Dodatkowe oczekiwanie Marinade w pierwszej epoce po zleceniu (30h)
+    pub const EXTRA_WAIT_SECS: i64 = 30 * 60 * 60;
+
+    /// Czy bilet Marinade można już odebrać
+    pub fn is_ready(&self, clock: &Clock) -> bool {
+        if clock.epoch <= self.created_epoch {
+            return false;
+        }
+        clock.epoch > self.created_epoch + 1
+            || clock.unix_timestamp.saturating_sub(clock.epoch_start_timestamp) >= Self::EXTRA_WAIT_SECS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket(created_epoch: u64) -> UnstakeTicket {
+        UnstakeTicket {
+            user: Pubkey::default(),
+            user_stake: Pubkey::default(),
+            marinade_ticket: Pubkey::default(),
+            msol_amount: 1_000_000_000,
+            lamports_amount: 1_100_000_000,
+            dividend: 100_000_000,
+            created_epoch,
+            bump: 255,
+        }
+    }
+
+    fn clock(epoch: u64, seconds_into_epoch: i64) -> Clock {
+        let epoch_start_timestamp = 1_700_000_000;
+        Clock {
+            slot: 0,
+            epoch_start_timestamp,
+            epoch,
+            leader_schedule_epoch: epoch,
+            unix_timestamp: epoch_start_timestamp + seconds_into_epoch,
+        }
+    }
+
+    #[test]
+    fn ticket_not_ready_in_order_epoch() {
+        assert!(!ticket(500).is_ready(&clock(500, 3 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn ticket_not_ready_before_extra_wait() {
+        // Kolejna epoka, ale Marinade jeszcze nie zrealizowała unstake
+        let ticket = ticket(500);
+        assert!(!ticket.is_ready(&clock(501, 0)));
+        assert!(!ticket.is_ready(&clock(501, UnstakeTicket::EXTRA_WAIT_SECS - 1)));
+    }
+
+    #[test]
+    fn ticket_ready_after_extra_wait() {
+        let ticket = ticket(500);
+        assert!(ticket.is_ready(&clock(501, UnstakeTicket::EXTRA_WAIT_SECS)));
+        assert!(ticket.is_ready(&clock(502, 0)));
+    }
+}
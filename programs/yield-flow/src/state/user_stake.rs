@@ -5,17 +5,36 @@
 //    - Warianty:
 //      * Disabled - brak automatycznych wypłat
 //      * Daily - codzienne wypłaty
-//      * Weekly(u8) - cotygodniowe (0-6 = niedziela-sobota)
-//      * Monthly(u8) - comiesięczne (1-28 dzień miesiąca)
+//      * Weekly(u8, PayoutTime) - cotygodniowe (0-6 = niedziela-sobota)
+//      * Monthly(u8, PayoutTime) - comiesięczne (1-28 dzień miesiąca)
 //      * Custom(i64) - niestandardowy interwał w sekundach
 //    - Implementuje Default jako Disabled
 //
-// 2. UserStake - główna struktura przechowująca dane stakingu użytkownika
+// 2. PayoutTime - lokalna godzina wypłaty dla Weekly/Monthly
+//    - Pola:
+//      * hour: u8 - godzina w czasie lokalnym (0-23)
+//      * utc_offset_minutes: i16 - stałe przesunięcie względem UTC w minutach
+//        (np. 60 = CET, 120 = CEST, zakres -720..=840)
+//    - Default = północ UTC
+//
+// 3. PayoutMode - w czym realizowana jest dywidenda
+//    - Usdc - sprzedaż LST zysku przez Jupiter na token wypłaty pozycji
+//      (payout_mint, domyślnie USDC)
+//    - Sol - liquid unstake mSOL zysku w Marinade, SOL trafia do właściciela
+//    - DelayedSol - bilet opóźnionego unstake Marinade (bez opłaty), SOL
+//      odbierany po zakończeniu epoki (patrz state::UnstakeTicket)
+//
+// 4. Beneficiary - odbiorca części wypłaty
+//    - wallet: Pubkey - właściciel konta tokenu wypłaty beneficjenta
+//    - bps: u16 - udział w wypłacie (suma udziałów = MAX_BPS)
+//
+// 5. UserStake - główna struktura przechowująca dane stakingu użytkownika
 //    - Pola:
 //      * user: Pubkey - klucz użytkownika
-//      * msol_amount: u64 - ilość msol w stake'u
-//      * base_sol_value: u64 - bazowa wartość w SOL
-//      * last_update: i64 - timestamp ostatniej aktualizacji
+//      * asset: Pubkey - mint LST pozycji (patrz state::LstAsset)
+//      * shares: u64 - udziały w skarbcu aktywa (patrz state::Vault)
+//      * base_sol_value: u64 - bazowy kurs 1 tokenu LST w lamportach SOL
+//      * last_update: i64 - timestamp ostatniego naliczenia opłaty za zarządzanie
 //      * bump: u8 - wartość bump dla PDA
 //      * last_dividend: u64 - ostatnia wypłacona dywidenda
 //      * total_dividends: u64 - łączne wypłacone dywidendy
@@ -23,26 +42,52 @@
 //      * next_payout_date: i64 - data następnej wypłaty
 //      * min_dividend_amount: u64 - minimalna kwota do wypłaty
 //      * auto_claim_enabled: bool - czy auto-wypłata jest włączona
+//      * payout_mode: PayoutMode - waluta wypłaty dywidendy
+//      * payout_mint: Pubkey - token wypłaty w trybie Usdc (patrz state::PayoutToken)
+//      * max_unstake_fee_bps: u16 - maksymalna opłata liquid unstake (tryb Sol)
+//      * ticket_count: u64 - liczba utworzonych biletów unstake (seed kolejnego)
+//      * auto_compound: bool - czy zysk może być kapitalizowany (compound_dividend)
+//      * total_compounded: u64 - łączna skapitalizowana dywidenda w lamportach
+//      * payout_bps: u16 - wypłacana część dywidendy (reszta kapitalizowana)
+//      * total_fees: u64 - łączne opłaty protokołu pobrane z dywidend pozycji (lamporty)
+//      * beneficiaries: Vec<Beneficiary> - odbiorcy wypłaty (max MAX_BENEFICIARIES,
+//        pusta lista = całość do właściciela)
+//    - PDA ["user-stake", user, asset] - jedna pozycja na użytkownika i aktywo
 
 
 
 use anchor_lang::prelude::*;
 use anchor_lang::AnchorDeserialize;
+use crate::utils::constants::MAX_BENEFICIARIES;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PayoutTime {
+    pub hour: u8,                // 0-23 (czas lokalny)
+    pub utc_offset_minutes: i16, // -720..=840
+}
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
 pub enum PayoutSchedule {
+    #[default]
     Disabled,
     Daily,
-    Weekly(u8),  // 0-6 (niedziela-sobota)
-    Monthly(u8), // 1-28
-    Custom(i64), // Interwał w sekundach
+    Weekly(u8, PayoutTime),  // 0-6 (niedziela-sobota)
+    Monthly(u8, PayoutTime), // 1-28
+    Custom(i64),             // Interwał w sekundach
 }
 
-// Ręczna implementacja Default dla PayoutSchedule
-impl Default for PayoutSchedule {
-    fn default() -> Self {
-        PayoutSchedule::Disabled // Domyślna wartość
-    }
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Beneficiary {
+    pub wallet: Pubkey,
+    pub bps: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PayoutMode {
+    #[default]
+    Usdc, // Swap przez Jupiter
+    Sol,  // Marinade liquid unstake
+    DelayedSol, // Bilet Marinade order_unstake
 }
 
 #[account]
@@ -50,7 +95,8 @@ impl Default for PayoutSchedule {
 
 pub struct UserStake {
     pub user: Pubkey,
-    pub msol_amount: u64,
+    pub asset: Pubkey,
+    pub shares: u64,
     pub base_sol_value: u64,
     pub last_update: i64,
     pub bump: u8,
@@ -62,8 +108,26 @@ pub struct UserStake {
     pub next_payout_date: i64,
     pub min_dividend_amount: u64,
     pub auto_claim_enabled: bool,
+
+    // Pola trybu wypłaty
+    pub payout_mode: PayoutMode,
+    pub payout_mint: Pubkey,
+    pub max_unstake_fee_bps: u16,
+    pub ticket_count: u64,
+
+    // Pola kapitalizacji
+    pub auto_compound: bool,
+    pub total_compounded: u64,
+    pub payout_bps: u16,
+    pub total_fees: u64,
+
+    // Podział wypłaty
+    pub beneficiaries: Vec<Beneficiary>,
 }
 
 impl UserStake {
-    pub const LEN: usize = 32 + 8*5 + 1 + 1 + 8*2 + 1;
+    // 8 bajtów dyskryminatora + 2 * Pubkey + 7 * u64/i64 + bump + PayoutSchedule (1 + max(8, 1 + 3)) + bool
+    // + PayoutMode + Pubkey + u16 + u64 + bool + u64 + u16 + u64 + Vec<Beneficiary> (4 + MAX_BENEFICIARIES * (32 + 2))
+    pub const LEN: usize = 8 + 32*2 + 8*7 + 1 + (1 + 8) + 1 + 1 + 32 + 2 + 8 + 1 + 8 + 2 + 8
+        + 4 + MAX_BENEFICIARIES * (32 + 2);
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+// Stan skarbca (vault) przechowującego tokeny LST użytkowników
+//
+// Pola:
+// - mint: Pubkey - mint LST przechowywanego w skarbcu (patrz state::LstAsset)
+// - token_account: Pubkey - konto tokenowe (PDA) z LST wszystkich użytkowników
+// - total_shares: u64 - łączna liczba wyemitowanych udziałów
+// - bump: u8 - wartość bump dla PDA stanu skarbca
+// - token_bump: u8 - wartość bump dla PDA konta tokenowego
+//
+// Adresy:
+// - Jeden skarbiec na aktywo: PDA ["vault", mint], konto tokenowe
+//   PDA ["vault-lst", mint]
+//
+// Udziały:
+// - Użytkownik otrzymuje udziały proporcjonalnie do wpłaconego LST
+// - Wartość udziału = saldo token_account / total_shares
+// - UserStake przechowuje wyłącznie udziały, nigdy deklarowaną ilość LST
+//
+// - impl Vault - zawiera stałe LEN i seedy PDA
+
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Vault {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub total_shares: u64,
+    pub bump: u8,
+    pub token_bump: u8,
+}
+
+impl Vault {
+    pub const SEED: &'static [u8] = b"vault";
+    pub const TOKEN_SEED: &'static [u8] = b"vault-lst";
+
+    // 8 bajtów dyskryminatora + 2 * Pubkey + u64 + 2 * u8
+    pub const LEN: usize = 8 + 32 * 2 + 8 + 1 + 1;
+}
@@ -1,171 +1,264 @@
 // Moduł integracji z Jupiter DEX Aggregator
 //
 // Główne funkcjonalności:
-// 1. swap_sol_to_usdc() - wykonuje wymianę SOL na USDC poprzez Jupiter DEX Aggregator
+// 1. swap_via_route() - wykonuje swap przez program Jupiter na podstawie
+//    trasy (route) przygotowanej po stronie klienta
 //    - Parametry:
-//      * ctx: &Context<Swap> - kontekst z wymaganymi kontami
-//      * sol_amount: u64 - ilość SOL w lamports do wymiany
+//      * jupiter_program: AccountInfo - program Jupiter (weryfikowany adres)
+//      * route_accounts: &[AccountInfo] - konta trasy (remaining_accounts)
+//      * route_data: Vec<u8> - nieprzezroczyste dane instrukcji Jupitera
+//      * source / destination: konta tokenowe wejścia i wyjścia
+//      * max_in: u64 - maksymalna ilość tokenów wejściowych
+//      * min_out: u64 - minimalna ilość tokenów wyjściowych
+//      * signer_seeds - seedy PDA gdy źródłem jest konto programu
 //    - Proces:
-//      1. Pobiera aktualny kurs SOL/USDC (np. z Oracla Pyth)
-//      2. Oblicza minimalną oczekiwaną ilość USDC (z uwzględnieniem poślizgu 0.5%)
-//      3. Przygotowuje dane transakcji
-//      4. Wykonuje CPI (Cross-Program Invocation) do programu Jupiter
-//      5. Zwraca otrzymaną ilość USDC
-//
-// 2. Struktura Swap<'info> - definicja wymaganych kont:
-//    - user: Signer<'info> - portfel użytkownika
-//    - wsol_account: TokenAccount - konto WSOL użytkownika
-//    - usdc_account: TokenAccount - konto USDC użytkownika
-//    - wsol_mint: Mint - mint WSOL (tokenized SOL)
-//    - usdc_mint: Mint - mint USDC
-//    - jupiter_program: Program - program Jupiter DEX Aggregator
-//    - token_program: Program - program tokenowy SPL
-//    - associated_token_program: Program - program kont powiązanych
+//      1. Weryfikuje konta trasy (route_accounts_metas)
+//      2. Zapamiętuje salda source i destination
+//      3. Wykonuje CPI do programu Jupiter z kontami i danymi klienta
+//      4. Przeładowuje konta i liczy faktyczne zmiany sald
+//      5. Weryfikuje: wydane <= max_in, otrzymane >= min_out, konta skarbca
+//         bez delegata i z niezmienionym właścicielem
 //
 // Stałe:
-// - JUPITER_PROGRAM_ID: stały adres programu Jupitera
-// - SLIPPAGE_BPS: 50 (0.5% dopuszczalnego poślizgu)
-//
-// Funkcje pomocnicze:
-// - get_sol_usdc_rate() - symulacja pobrania kursu (w rzeczywistości integracja z Oracle)
-// - calculate_min_out() - oblicza minimalną oczekiwaną ilość USDC z uwzględnieniem poślizgu
+// - JUPITER_PROGRAM_ID: adres programu Jupiter v6
 //
 // Obsługa błędów:
-// - SlippageExceeded - przekroczono dopuszczalny poślizg
-// - Overflow - błąd obliczeń matematycznych
-// - InsufficientLiquidity - brak płynności na rynku
+// - InvalidJupiterProgram - niepoprawny program Jupiter
+// - SlippageExceeded - otrzymano mniej niż min_out
+// - SwapInputExceeded - trasa pobrała więcej niż max_in
+// - InvalidSwapRoute - PDA poza pozycją authority konta źródłowego, inne
+//   konta tokenowe PDA lub konta programu w trasie, delegat po swapie
 //
 // Uwagi:
-// - Moduł wykorzystuje CPI do interakcji z programem Jupiter
-// - Wymaga kont WSOL i USDC utworzonych jako Associated Token Accounts
-// - W rzeczywistej implementacji należy dodać prawdziwą integrację z Oracle
+// - Program nie interpretuje danych trasy - bezpieczeństwo wynika z różnicy
+//   sald przed i po CPI oraz z ograniczenia kont, którymi rozporządza PDA
+// - Flagi signer/writable kont przechodzą z transakcji; PDA podpisujące
+//   (signer_seeds) oznaczane jako signer tylko bezpośrednio przed kontem
+//   źródłowym (user_transfer_authority w route i shared_accounts_route Jupitera)
+// - Jedyne konta tokenowe PDA w trasie to source i destination
 
 use anchor_lang::{
     prelude::*,
-    solana_program::{program::invoke, system_instruction}
-};
-use anchor_spl::{
-    token::{Mint, Token, TokenAccount},
-    associated_token::AssociatedToken
+    solana_program::{instruction::{AccountMeta, Instruction}, program::invoke_signed, pubkey}
 };
+use anchor_spl::token::{self, TokenAccount};
+use crate::errors::ErrorCode;
+
+/// Adres programu Jupiter Aggregator v6
+pub const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+/// Faktyczny wynik swapu (z różnicy sald)
+#[derive(Clone, Copy, Debug)]
+pub struct SwapResult {
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// Wykonuje swap przez Jupiter na podstawie trasy przekazanej przez klienta
+#[allow(clippy::too_many_arguments)]
+pub fn swap_via_route<'info>(
+    jupiter_program: &AccountInfo<'info>,
+    route_accounts: &[AccountInfo<'info>],
+    route_data: Vec<u8>,
+    source: &mut Account<'info, TokenAccount>,
+    destination: &mut Account<'info, TokenAccount>,
+    max_in: u64,
+    min_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<SwapResult> {
+    require_keys_eq!(jupiter_program.key(), JUPITER_PROGRAM_ID, ErrorCode::InvalidJupiterProgram);
+
+    // 1. Konta trasy - PDA podpisuje tylko jako authority konta źródłowego
+    let pda_signers: Vec<Pubkey> = signer_seeds
+        .iter()
+        .filter_map(|seeds| Pubkey::create_program_address(seeds, &crate::ID).ok())
+        .collect();
+    let accounts = route_account_metas(route_accounts, &source.key(), &destination.key(), &pda_signers)?;
+
+    // 2. Salda przed swapem
+    let source_before = source.amount;
+    let destination_before = destination.amount;
+
+    // 3. CPI do Jupitera z kontami i danymi trasy
+    let instruction = Instruction {
+        program_id: JUPITER_PROGRAM_ID,
+        accounts,
+        data: route_data,
+    };
+
+    let mut account_infos = route_accounts.to_vec();
+    account_infos.push(jupiter_program.clone());
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    // 4. Faktyczne zmiany sald
+    let (source_owner, destination_owner) = (source.owner, destination.owner);
+    source.reload()?;
+    destination.reload()?;
+    let amount_in = source_before.saturating_sub(source.amount);
+    let amount_out = destination.amount.saturating_sub(destination_before);
+
+    // 5. Weryfikacja - trasa nie może zostawić uprawnień do kont PDA
+    require!(amount_in <= max_in, ErrorCode::SwapInputExceeded);
+    require!(amount_out >= min_out, ErrorCode::SlippageExceeded);
+    if !pda_signers.is_empty() {
+        require!(
+            source.owner == source_owner && destination.owner == destination_owner,
+            ErrorCode::InvalidSwapRoute
+        );
+        require!(
+            source.delegate.is_none() && destination.delegate.is_none()
+                && source.close_authority.is_none() && destination.close_authority.is_none(),
+            ErrorCode::InvalidSwapRoute
+        );
+    }
+
+    Ok(SwapResult { amount_in, amount_out })
+}
+
+/// Konta instrukcji Jupitera z kont trasy klienta
+///
+/// PDA z `pda_signers` oznaczane jako signer tylko bezpośrednio przed kontem
+/// źródłowym (authority przelewu). Odrzuca trasy bez takiej pary, z kontami
+/// programu (poza samym PDA) oraz z kontami tokenowymi innymi niż source
+/// i destination, którymi PDA rozporządza (właściciel lub delegat).
+fn route_account_metas(
+    route_accounts: &[AccountInfo],
+    source: &Pubkey,
+    destination: &Pubkey,
+    pda_signers: &[Pubkey]
+) -> Result<Vec<AccountMeta>> {
+    let authority_index = route_accounts
+        .windows(2)
+        .position(|pair| pair[1].key == source && pda_signers.contains(pair[0].key));
+    require!(
+        authority_index.is_some() || pda_signers.is_empty(),
+        ErrorCode::InvalidSwapRoute
+    );
 
-/// Moduł do integracji z Jupiter DEX Aggregator
-pub mod jupiter {
+    let mut accounts = Vec::with_capacity(route_accounts.len());
+    for (index, account) in route_accounts.iter().enumerate() {
+        let is_pda = pda_signers.contains(account.key);
+        require!(is_pda || account.owner != &crate::ID, ErrorCode::InvalidSwapRoute);
+
+        if account.owner == &token::ID && account.key != source && account.key != destination {
+            if let Ok(token_account) = TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..]) {
+                let delegate: Option<Pubkey> = token_account.delegate.into();
+                require!(
+                    !pda_signers.contains(&token_account.owner)
+                        && !matches!(delegate, Some(delegate) if pda_signers.contains(&delegate)),
+                    ErrorCode::InvalidSwapRoute
+                );
+            }
+        }
+
+        accounts.push(AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer || (is_pda && Some(index) == authority_index),
+            is_writable: account.is_writable,
+        });
+    }
+
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+
+    struct TestAccount {
+        key: Pubkey,
+        owner: Pubkey,
+        lamports: u64,
+        data: Vec<u8>,
+    }
 
-    // Stałe
-    const JUPITER_PROGRAM_ID: Pubkey = pubkey!("JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB"); // Adres programu Jupitera
-    const SLIPPAGE_BPS: u64 = 50; // 0.5% dopuszczalnego poślizgu
-
-    /// Wykonuje swap SOL → USDC przez Jupiter DEX Aggregator
-    pub fn swap_sol_to_usdc(
-        ctx: &Context<Swap>,
-        sol_amount: u64, // Ilość SOL w lamports
-    ) -> Result<u64> {
-        // 1. Pobierz aktualny kurs z Oracla (np. Pyth)
-        let usdc_per_sol = get_sol_usdc_rate()?;
-
-        // 2. Oblicz minimalną oczekiwaną ilość USDC (z uwzględnieniem poślizgu)
-        let min_out_amount = calculate_min_out(sol_amount, usdc_per_sol)?;
-
-        // 3. Przygotuj dane swapu
-        let swap_data = JupiterSwapData {
-            amount_in: sol_amount,
-            min_amount_out: min_out_amount,
-            platform_fee_bps: 0, // Brak dodatkowych opłat
-        };
-
-        // 4. Wywołaj CPI do programu Jupiter
-        let cpi_program = ctx.accounts.jupiter_program.to_account_info();
-        let cpi_accounts = jupiter_ix::Swap {
-            user: ctx.accounts.user.to_account_info(),
-            input_token_account: ctx.accounts.wsol_account.to_account_info(),
-            output_token_account: ctx.accounts.usdc_account.to_account_info(),
-            // ... inne wymagane konta
-        };
-
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-        jupiter_ix::swap(cpi_ctx, swap_data)?;
-
-        // 5. Zwróć otrzymaną ilość USDC
-        Ok(ctx.accounts.usdc_account.amount)
+    impl TestAccount {
+        fn new(owner: Pubkey, data: Vec<u8>) -> Self {
+            Self { key: Pubkey::new_unique(), owner, lamports: 0, data }
+        }
+
+        fn token(authority: Pubkey, delegate: Option<Pubkey>) -> Self {
+            let account = spl_token::state::Account {
+                owner: authority,
+                delegate: delegate.into(),
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            };
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            account.pack_into_slice(&mut data);
+            Self::new(token::ID, data)
+        }
+
+        fn info(&mut self) -> AccountInfo<'_> {
+            AccountInfo::new(&self.key, false, true, &mut self.lamports, &mut self.data, &self.owner, false, 0)
+        }
     }
 
-    /// Struktura kont wymaganych do swapu
-    #[derive(Accounts)]
-    pub struct Swap<'info> {
-        /// Portfel użytkownika (signer)
-        #[account(mut)]
-        pub user: Signer<'info>,
-
-        /// Konto WSOL użytkownika
-        #[account(
-            mut,
-            associated_token::mint = wsol_mint,
-            associated_token::authority = user
-        )]
-        pub wsol_account: Box<Account<'info, TokenAccount>>,
-
-        /// Konto USDC użytkownika
-        #[account(
-            mut,
-            associated_token::mint = usdc_mint,
-            associated_token::authority = user
-        )]
-        pub usdc_account: Box<Account<'info, TokenAccount>>,
-
-        /// Mint WSOL
-        #[account(address = spl_token::native_mint::ID)]
-        pub wsol_mint: Box<Account<'info, Mint>>,
-
-        /// Mint USDC
-        pub usdc_mint: Box<Account<'info, Mint>>,
-
-        /// Program Jupiter
-        #[account(address = JUPITER_PROGRAM_ID)]
-        pub jupiter_program: Program<'info, Jupiter>,
-
-        /// Program tokenowy
-        pub token_program: Program<'info, Token>,
-
-        /// Program Associated Token
-        pub associated_token_program: Program<'info, AssociatedToken>,
+    fn metas(accounts: &mut [TestAccount], source: usize, destination: usize, pda: &Pubkey) -> Result<Vec<AccountMeta>> {
+        let (source, destination) = (accounts[source].key, accounts[destination].key);
+        let infos: Vec<AccountInfo> = accounts.iter_mut().map(TestAccount::info).collect();
+        route_account_metas(&infos, &source, &destination, &[*pda])
     }
 
-    // Helper: Pobiera aktualny kurs SOL/USDC
-    fn get_sol_usdc_rate() -> Result<u64> {
-        // Implementacja integracji z Oracle (np. Pyth Network)
-        // W przykładzie stała wartość 100 USDC/SOL
-        Ok(100 * 1_000_000) // 100 USDC z dokładnością 6 miejsc
+    #[test]
+    fn pda_signs_only_as_source_authority() {
+        let vault = TestAccount::new(crate::ID, vec![]);
+        let pda = vault.key;
+        let mut accounts = vec![
+            TestAccount::new(Pubkey::default(), vec![]),
+            vault,
+            TestAccount::token(pda, None),
+            TestAccount::token(pda, None),
+            TestAccount::new(Pubkey::default(), vec![]),
+        ];
+        // PDA powtórzone w dalszej części trasy nie jest oznaczane jako signer
+        accounts[4].key = pda;
+
+        let metas = metas(&mut accounts, 2, 3, &pda).unwrap();
+        let signers: Vec<usize> = (0..metas.len()).filter(|&index| metas[index].is_signer).collect();
+        assert_eq!(signers, vec![1]);
     }
 
-    // Helper: Oblicza minimalną oczekiwaną ilość USDC
-    fn calculate_min_out(sol_amount: u64, rate: u64) -> Result<u64> {
-        let expected = sol_amount
-            .checked_mul(rate)
-            .ok_or(ErrorCode::Overflow)?
-            .checked_div(1_000_000_000)
-            .ok_or(ErrorCode::Overflow)?;
-
-        expected
-            .checked_sub(
-                expected
-                    .checked_mul(SLIPPAGE_BPS)
-                    .ok_or(ErrorCode::Overflow)?
-                    .checked_div(10_000)
-                    .ok_or(ErrorCode::Overflow)?
-            )
-            .ok_or(ErrorCode::Overflow)
+    #[test]
+    fn rejects_route_without_source_authority() {
+        let vault = TestAccount::new(crate::ID, vec![]);
+        let pda = vault.key;
+        let mut accounts = vec![TestAccount::token(pda, None), vault, TestAccount::token(pda, None)];
+        assert!(metas(&mut accounts, 0, 2, &pda).is_err());
+    }
+
+    #[test]
+    fn rejects_other_vault_token_accounts() {
+        let vault = TestAccount::new(crate::ID, vec![]);
+        let pda = vault.key;
+        let mut accounts = vec![
+            vault,
+            TestAccount::token(pda, None),
+            TestAccount::token(pda, None),
+            TestAccount::token(pda, None),
+        ];
+        assert!(metas(&mut accounts, 1, 2, &pda).is_err());
+
+        // Konto obce z PDA jako delegatem
+        accounts[3] = TestAccount::token(Pubkey::new_unique(), Some(pda));
+        assert!(metas(&mut accounts, 1, 2, &pda).is_err());
+
+        accounts[3] = TestAccount::token(Pubkey::new_unique(), None);
+        assert!(metas(&mut accounts, 1, 2, &pda).is_ok());
     }
 
-    /// Własne kody błędów
-    #[error_code]
-    pub enum ErrorCode {
-        #[msg("Przekroczono dopuszczalny poślizg")]
-        SlippageExceeded,
-        #[msg("Błąd obliczeń matematycznych")]
-        Overflow,
-        #[msg("Brak płynności na rynku")]
-        InsufficientLiquidity,
+    #[test]
+    fn rejects_program_accounts() {
+        let vault = TestAccount::new(crate::ID, vec![]);
+        let pda = vault.key;
+        let mut accounts = vec![
+            vault,
+            TestAccount::token(pda, None),
+            TestAccount::token(pda, None),
+            TestAccount::new(crate::ID, vec![0; 8]),
+        ];
+        assert!(metas(&mut accounts, 1, 2, &pda).is_err());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,62 @@
+// Moduł kursów LST z rejestru aktywów (state::LstAsset)
+//
+// Główne funkcjonalności:
+// 1. read_rate() - zweryfikowany odczyt kursu on-chain
+//    - Konto stanu puli musi być zarejestrowanym pool_state aktywa
+//    - Właścicielem konta musi być pool_program aktywa
+//    - Aktywo Marinade tylko z programem config.marinade_program
+//    - Kurs w lamportach SOL za 1 token (precyzja 1e9)
+//
+// 2. rate_from_pool_data() - kurs z danych konta puli wg RateReader
+//    - Marinade: State.calc_lamports_from_msol_amount (marinade::lamports_per_msol)
+//    - SplStakePool: total_lamports / pool_token_supply (stake_pool.rs)
+//    - Funkcja czysta - używana także przez keepera
+//
+// Obsługa błędów:
+// - InvalidStakePool - konto puli niezgodne z rejestrem
+// - InvalidMarinadeProgram - aktywo Marinade z innym programem niż w konfiguracji
+// - InvalidMarinadeState - niepoprawny State Marinade
+
+use anchor_lang::prelude::*;
+use marinade_finance::state::State;
+use crate::{
+    state::{LstAsset, ProgramConfig, RateReader},
+    utils::{marinade, stake_pool},
+    errors::ErrorCode
+};
+
+/// Odczytuje kurs aktywa z konta stanu jego puli
+pub fn read_rate(config: &ProgramConfig, asset: &LstAsset, pool_state: &AccountInfo) -> Result<u64> {
+    verify_pool_program(config, asset.rate_reader, &asset.pool_program)?;
+    require_keys_eq!(pool_state.key(), asset.pool_state, ErrorCode::InvalidStakePool);
+    require_keys_eq!(*pool_state.owner, asset.pool_program, ErrorCode::InvalidStakePool);
+
+    let data = pool_state.try_borrow_data()?;
+    rate_from_pool_data(asset, &data, Clock::get()?.epoch)
+}
+
+/// Program puli Marinade musi być programem Marinade z konfiguracji
+pub fn verify_pool_program(
+    config: &ProgramConfig,
+    rate_reader: RateReader,
+    pool_program: &Pubkey
+) -> Result<()> {
+    if rate_reader == RateReader::Marinade {
+        require_keys_eq!(*pool_program, config.marinade_program, ErrorCode::InvalidMarinadeProgram);
+    }
+    Ok(())
+}
+
+/// Kurs aktywa (lamporty za 1 token) z danych konta stanu puli
+pub fn rate_from_pool_data(asset: &LstAsset, data: &[u8], current_epoch: u64) -> Result<u64> {
+    match asset.rate_reader {
+        RateReader::Marinade => {
+            let state = State::try_deserialize(&mut &data[..])
+                .map_err(|_| ErrorCode::InvalidMarinadeState)?;
+            marinade::lamports_per_msol(&state)
+        }
+        RateReader::SplStakePool => {
+            stake_pool::lamports_per_pool_token(data, &asset.mint, current_epoch)
+        }
+    }
+}
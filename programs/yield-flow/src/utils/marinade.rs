@@ -8,7 +8,15 @@
 // Kluczowe komponenty:
 // - `deposit_sol()`: Konwersja SOL do mSOL z użyciem CPI (Cross-Program Invocation)
 // - `withdraw_stake_rewards()`: Wypłata nagród stakingowych w SOL
-// - `get_msol_rate()`: Pobieranie aktualnego kursu mSOL/SOL
+// - `lamports_per_msol()`: Kurs mSOL -> SOL z już zdeserializowanego State
+//   (lamporty za 1 mSOL, precyzja 1e9) - zweryfikowany odczyt on-chain
+//   przez utils::lst::read_rate
+// - `liquid_unstake()`: mSOL -> SOL przez pulę płynności Marinade (CPI)
+//   * mSOL pobierane z konta skarbca (podpis PDA Vault)
+//   * SOL trafia bezpośrednio na wskazane konto systemowe
+// - `order_unstake()`: mSOL -> bilet opóźnionego unstake (bez opłaty)
+//   * Beneficjentem biletu jest authority mSOL (PDA Vault)
+// - `claim_ticket()`: Odbiór SOL z biletu po zakończeniu epoki
 //
 // Struktury kont:
 // - `DepositSol`: Konta wymagane do depozytu SOL
@@ -23,24 +31,142 @@
 // - LAMPORTS_PER_SOL: 1_000_000_000 (1 SOL w lamportach)
 // - MARINADE_PROGRAM_ID: Stały adres programu Marinade Finance
 
-use anchor_lang::{
-    prelude::*,
-    solana_program::{program::invoke, system_instruction}
-};
+use anchor_lang::prelude::*;
 use marinade_finance::{
     instruction as marinade_ix, 
     state::State,
     ID as MARINADE_PROGRAM_ID
 };
 use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::{
+    utils::constants::LAMPORTS_PER_SOL,
+    errors::ErrorCode as YieldFlowError
+};
+
+// ======================== KURS mSOL ======================== //
+
+/// Wartość 1 mSOL w lamportach (kierunek mSOL -> SOL)
+pub fn lamports_per_msol(state: &State) -> Result<u64> {
+    let rate = state
+        .calc_lamports_from_msol_amount(LAMPORTS_PER_SOL)
+        .map_err(|_| YieldFlowError::MsolRateCalculationError)?;
+    require!(rate > 0, YieldFlowError::MsolRateCalculationError);
+    Ok(rate)
+}
+
+// ======================== LIQUID UNSTAKE ======================== //
+
+/// Konta puli płynności Marinade wymagane przez liquid_unstake
+pub struct LiquidUnstakeAccounts<'a, 'info> {
+    pub marinade_program: &'a AccountInfo<'info>,
+    pub state: &'a AccountInfo<'info>,
+    pub msol_mint: &'a AccountInfo<'info>,
+    pub liq_pool_sol_leg: &'a AccountInfo<'info>,
+    pub liq_pool_msol_leg: &'a AccountInfo<'info>,
+    pub treasury_msol_account: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+/// Wymienia `msol_amount` mSOL na SOL w puli płynności Marinade
+pub fn liquid_unstake<'info>(
+    accounts: LiquidUnstakeAccounts<'_, 'info>,
+    msol_from: &AccountInfo<'info>,
+    msol_authority: &AccountInfo<'info>,
+    sol_to: &AccountInfo<'info>,
+    msol_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = marinade_finance::cpi::accounts::LiquidUnstake {
+        state: accounts.state.clone(),
+        msol_mint: accounts.msol_mint.clone(),
+        liq_pool_sol_leg_pda: accounts.liq_pool_sol_leg.clone(),
+        liq_pool_msol_leg: accounts.liq_pool_msol_leg.clone(),
+        treasury_msol_account: accounts.treasury_msol_account.clone(),
+        get_msol_from: msol_from.clone(),
+        get_msol_from_authority: msol_authority.clone(),
+        transfer_sol_to: sol_to.clone(),
+        system_program: accounts.system_program.clone(),
+        token_program: accounts.token_program.clone(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.marinade_program.clone(),
+        cpi_accounts,
+        signer_seeds
+    );
+
+    marinade_finance::cpi::liquid_unstake(cpi_ctx, msol_amount)
+}
+
+// ======================== DELAYED UNSTAKE ======================== //
+
+/// Rozmiar konta biletu Marinade (dyskryminator + state + beneficiary
+/// + lamports_amount + created_epoch)
+pub const TICKET_ACCOUNT_LEN: usize = 8 + 32 + 32 + 8 + 8;
+
+/// Zleca opóźniony unstake `msol_amount` mSOL do istniejącego (pustego) biletu
+#[allow(clippy::too_many_arguments)]
+pub fn order_unstake<'info>(
+    marinade_program: &AccountInfo<'info>,
+    state: &AccountInfo<'info>,
+    msol_mint: &AccountInfo<'info>,
+    msol_from: &AccountInfo<'info>,
+    msol_authority: &AccountInfo<'info>,
+    ticket_account: &AccountInfo<'info>,
+    clock: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    msol_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_accounts = marinade_finance::cpi::accounts::OrderUnstake {
+        state: state.clone(),
+        msol_mint: msol_mint.clone(),
+        burn_msol_from: msol_from.clone(),
+        burn_msol_authority: msol_authority.clone(),
+        new_ticket_account: ticket_account.clone(),
+        clock: clock.clone(),
+        rent: rent.clone(),
+        token_program: token_program.clone(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        marinade_program.clone(),
+        cpi_accounts,
+        signer_seeds
+    );
+
+    marinade_finance::cpi::order_unstake(cpi_ctx, msol_amount)
+}
+
+/// Odbiera SOL z biletu - trafia na konto beneficjenta biletu
+pub fn claim_ticket<'info>(
+    marinade_program: &AccountInfo<'info>,
+    state: &AccountInfo<'info>,
+    reserve_pda: &AccountInfo<'info>,
+    ticket_account: &AccountInfo<'info>,
+    beneficiary: &AccountInfo<'info>,
+    clock: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let cpi_accounts = marinade_finance::cpi::accounts::Claim {
+        state: state.clone(),
+        reserve_pda: reserve_pda.clone(),
+        ticket_account: ticket_account.clone(),
+        transfer_sol_to: beneficiary.clone(),
+        clock: clock.clone(),
+        system_program: system_program.clone(),
+    };
+
+    marinade_finance::cpi::claim(CpiContext::new(marinade_program.clone(), cpi_accounts))
+}
 
 /// Moduł pomocniczy do integracji z Marinade Finance
+#[allow(clippy::module_inception)]
 pub mod marinade {
     use super::*;
 
-    // Stała określająca liczbę lamportów w 1 SOL (1 SOL = 1 miliard lamportów)
-    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
-
     // ======================== DEPOZYT SOL ======================== //
 
     /// Wykonuje depozyt SOL -> mSOL poprzez Marinade
@@ -48,7 +174,7 @@ pub mod marinade {
         ctx: &Context<DepositSol>,
         amount_lamports: u64,
     ) -> Result<()> {
-        require!(amount_lamports > 0, ErrorCode::InvalidAmount);
+        require!(amount_lamports > 0, YieldFlowError::InvalidAmount);
     
         let cpi_accounts = marinade_finance::cpi::accounts::Deposit {
             state: ctx.accounts.state.to_account_info(),
@@ -119,13 +245,6 @@ pub mod marinade {
 
     // ======================== POZOSTAŁE FUNKCJE ======================== //
 
-    /// Pobiera aktualny kurs mSOL/SOL w lamportach
-    pub fn get_msol_rate(marinade_state: &mut Account<State>) -> Result<u64> {
-        marinade_state.reload()?;
-        marinade_state.calc_msol_from_sol(LAMPORTS_PER_SOL)
-            .map_err(|_| ErrorCode::BłądObliczaniaKursu.into())
-    }
-
     /// Wypłaca nagrody stakingowe
     pub fn withdraw_stake_rewards(
         ctx: &Context<WithdrawRewards>,
@@ -148,11 +267,12 @@ pub mod marinade {
             rent: ctx.accounts.rent.to_account_info(),
         };
 
-        let (pda, bump) = Pubkey::find_program_address(
+        let (_, bump) = Pubkey::find_program_address(
             &[b"treasury", &ctx.accounts.state.key().to_bytes()],
             &MARINADE_PROGRAM_ID
         );
-        let signer = &[&[b"treasury", &ctx.accounts.state.key().to_bytes(), &[bump]][..]];
+        let state_key = ctx.accounts.state.key();
+        let signer: &[&[&[u8]]] = &[&[b"treasury", state_key.as_ref(), &[bump]]];
 
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.marinade_program.to_account_info(),
@@ -161,14 +281,13 @@ pub mod marinade {
         );
 
         marinade_ix::withdraw_stake_rewards(cpi_ctx, amount_lamports)
-            .map_err(|e| e.into())
     }
 
     // ======================== STRUKTURY ======================== //
 
     #[derive(Accounts)]
     pub struct WithdrawRewards<'info> {
-        #[account(mut, address = State::id() @ ErrorCode::InvalidMarinadeState)]
+        #[account(mut, address = State::id() @ YieldFlowError::InvalidMarinadeState)]
         pub state: Account<'info, State>,
         
         #[account(mut, seeds = [b"reserve"], bump, seeds::program = MARINADE_PROGRAM_ID)]
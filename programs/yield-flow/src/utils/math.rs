@@ -35,9 +35,32 @@
 //    - Sumę odsetek w lamportach
 //    - Błąd jeśli wystąpi overflow
 //
+// 3. Księgowanie udziałów w skarbcu (vault):
+//    - shares_for_deposit() - liczba udziałów za wpłacone mSOL
+//    - deposit_shares() - udziały wpłacającego i udziały zablokowane przy
+//      pierwszym depozycie (ochrona przed zawyżeniem ceny udziału)
+//    - shares_to_msol() - ilość mSOL przypadająca na udziały
+//    - msol_to_shares_ceil() - udziały do spalenia przy wypłacie mSOL
+//    - weighted_base_value() - uśredniony kurs bazowy po dopłacie
+//    - dividend_to_msol() - ilość mSOL odpowiadająca dywidendzie w lamportach
+//
+//    Zaokrąglenia zawsze na korzyść skarbca (użytkownik nigdy nie
+//    dostaje więcej mSOL niż faktycznie wpłacił)
+//
+// 4. calculate_bps() - część kwoty wyrażona w punktach bazowych
+//    (zaokrąglenie w dół)
+//    - split_keeper_bounty() - nagroda keepera i część właściciela wypłaty
+//
+// 5. split_by_bps() - podział kwoty między odbiorców wg punktów bazowych
+//    (części zaokrąglane w dół, reszta trafia do pierwszego odbiorcy)
+//
+// 6. management_fee() - roczna opłata za zarządzanie naliczana liniowo
+//    za każdą sekundę (zaokrąglenie w dół)
+//
 // Stałe:
 // - 1_000_000_000 - precyzja konwersji mSOL do SOL
 // - 10_000 - podstawa dla punktów bazowych (1% = 100)
+// - SECONDS_PER_YEAR - podstawa naliczania opłaty rocznej (365 dni)
 //
 // Obsługa błędów:
 // - DividendTooSmall - gdy aktualna wartość ≤ początkowej
@@ -50,7 +73,7 @@
 
 
 use anchor_lang::prelude::*;
-use crate::errors::ErrorCode;
+use crate::{errors::ErrorCode, utils::constants::{LOCKED_VAULT_SHARES, SECONDS_PER_YEAR}};
 
 
 pub fn calculate_dividend(
@@ -97,5 +120,432 @@ pub fn calculate_compound_interest(
             .ok_or(ErrorCode::MathOverflow)?;
     }
 
-    Ok((amount - principal as u128) as u64)
-}
\ No newline at end of file
+    u64::try_from(amount - principal as u128).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Liczba udziałów emitowanych za `msol_amount` wpłacone do skarbca
+pub fn shares_for_deposit(
+    msol_amount: u64,
+    total_shares: u64,
+    vault_balance: u64
+) -> Result<u64> {
+    // Pierwszy depozyt (lub pusty skarbiec) - kurs 1:1
+    if total_shares == 0 || vault_balance == 0 {
+        return Ok(msol_amount);
+    }
+
+    let shares = (msol_amount as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(vault_balance as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Udziały emitowane za depozyt: (udziały wpłacającego, udziały zablokowane)
+///
+/// Pierwszy depozyt do skarbca blokuje LOCKED_VAULT_SHARES udziałów bez
+/// właściciela - zawyżenie ceny udziału przelewem LST prosto na konto skarbca
+/// kosztuje atakującego wielokrotność straty kolejnych wpłacających.
+/// Depozyt, za który wpłacający nie dostałby udziałów, jest odrzucany.
+pub fn deposit_shares(
+    msol_amount: u64,
+    total_shares: u64,
+    vault_balance: u64
+) -> Result<(u64, u64)> {
+    let shares = shares_for_deposit(msol_amount, total_shares, vault_balance)?;
+    let locked = if total_shares == 0 { LOCKED_VAULT_SHARES } else { 0 };
+    let user_shares = shares.saturating_sub(locked);
+    require!(user_shares > 0, ErrorCode::InvalidAmount);
+
+    Ok((user_shares, locked))
+}
+
+
+/// Ilość mSOL w skarbcu przypadająca na `shares` udziałów (zaokrąglenie w dół)
+pub fn shares_to_msol(
+    shares: u64,
+    total_shares: u64,
+    vault_balance: u64
+) -> Result<u64> {
+    if total_shares == 0 {
+        return Ok(0);
+    }
+
+    let msol = (shares as u128)
+        .checked_mul(vault_balance as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(total_shares as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    u64::try_from(msol).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Liczba udziałów do spalenia przy wypłacie `msol_amount` (zaokrąglenie w górę)
+pub fn msol_to_shares_ceil(
+    msol_amount: u64,
+    total_shares: u64,
+    vault_balance: u64
+) -> Result<u64> {
+    require!(vault_balance > 0, ErrorCode::InsufficientShares);
+
+    let numerator = (msol_amount as u128)
+        .checked_mul(total_shares as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let shares = numerator
+        .checked_add(vault_balance as u128 - 1)
+        .ok_or(ErrorCode::MathOverflow)?
+        / vault_balance as u128;
+
+    u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Średni ważony kurs bazowy po dopłacie `added_msol` po kursie `current_rate`
+///
+/// Zachowuje niewypłaconą dywidendę z dotychczasowej pozycji.
+/// Zaokrąglenie w górę - nowa dywidenda nigdy nie jest zawyżona.
+pub fn weighted_base_value(
+    existing_msol: u64,
+    base_sol_value: u64,
+    added_msol: u64,
+    current_rate: u64
+) -> Result<u64> {
+    let total_msol = (existing_msol as u128)
+        .checked_add(added_msol as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if total_msol == 0 {
+        return Ok(current_rate);
+    }
+
+    let weighted = (existing_msol as u128)
+        .checked_mul(base_sol_value as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_add(
+            (added_msol as u128)
+                .checked_mul(current_rate as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+    let base = weighted
+        .checked_add(total_msol - 1)
+        .ok_or(ErrorCode::MathOverflow)?
+        / total_msol;
+
+    u64::try_from(base).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Ilość mSOL odpowiadająca `dividend_lamports` po kursie `current_rate`
+/// (lamporty za 1 mSOL), zaokrąglenie w dół na korzyść skarbca
+pub fn dividend_to_msol(
+    dividend_lamports: u64,
+    current_rate: u64
+) -> Result<u64> {
+    require!(current_rate > 0, ErrorCode::MsolRateCalculationError);
+
+    let msol = (dividend_lamports as u128)
+        .checked_mul(1_000_000_000)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(current_rate as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    u64::try_from(msol).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+
+/// Część `amount` odpowiadająca `bps` punktom bazowym (zaokrąglenie w dół)
+pub fn calculate_bps(amount: u64, bps: u16) -> Result<u64> {
+    let part = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(10_000)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    u64::try_from(part).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Dzieli wypłatę na nagrodę keepera (`bounty_bps`, w dół) i część właściciela
+pub fn split_keeper_bounty(amount: u64, bounty_bps: u16) -> Result<(u64, u64)> {
+    let bounty = calculate_bps(amount, bounty_bps)?;
+    let owner_amount = amount
+        .checked_sub(bounty)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok((bounty, owner_amount))
+}
+
+/// Dzieli `amount` wg udziałów w bps - reszta z zaokrągleń trafia do pierwszej części
+pub fn split_by_bps(amount: u64, shares_bps: &[u16]) -> Result<Vec<u64>> {
+    let mut parts = shares_bps
+        .iter()
+        .map(|bps| calculate_bps(amount, *bps))
+        .collect::<Result<Vec<u64>>>()?;
+
+    let mut distributed: u64 = 0;
+    for part in parts.iter() {
+        distributed = distributed
+            .checked_add(*part)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    if let Some(first) = parts.first_mut() {
+        *first = first
+            .checked_add(amount.checked_sub(distributed).ok_or(ErrorCode::MathOverflow)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(parts)
+}
+
+
+/// Opłata za zarządzanie od `principal` lamportów za `elapsed_secs` sekund
+/// przy stawce rocznej `annual_bps` (zaokrąglenie w dół)
+pub fn management_fee(principal: u64, annual_bps: u16, elapsed_secs: u64) -> Result<u64> {
+    let fee = (principal as u128)
+        .checked_mul(annual_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_mul(elapsed_secs as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / (10_000u128 * SECONDS_PER_YEAR as u128);
+
+    u64::try_from(fee).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::constants::MAX_KEEPER_BOUNTY_BPS;
+
+    const RATE: u64 = 1_100_000_000; // 1.1 SOL za 1 LST
+
+    #[test]
+    fn first_deposit_mints_one_share_per_token() {
+        assert_eq!(shares_for_deposit(5_000, 0, 0).unwrap(), 5_000);
+        // Pusty skarbiec z pozostałymi udziałami - również 1:1
+        assert_eq!(shares_for_deposit(5_000, 1_000, 0).unwrap(), 5_000);
+    }
+
+    #[test]
+    fn deposit_shares_round_down() {
+        // 10 / 3 udziału za token - 1 token daje 3 udziały, nie 4
+        assert_eq!(shares_for_deposit(1, 10, 3).unwrap(), 3);
+        assert_eq!(shares_for_deposit(7, 1_000, 1_000).unwrap(), 7);
+        // Skarbiec urósł (zysk LST) - mniej udziałów za ten sam depozyt
+        assert_eq!(shares_for_deposit(100, 1_000, 1_100).unwrap(), 90);
+    }
+
+    #[test]
+    fn first_deposit_locks_shares() {
+        assert_eq!(deposit_shares(5_000, 0, 0).unwrap(), (5_000 - LOCKED_VAULT_SHARES, LOCKED_VAULT_SHARES));
+        assert_eq!(deposit_shares(5_000, 1_000, 1_000).unwrap(), (5_000, 0));
+        // Pierwszy depozyt nie większy niż blokada - brak udziałów dla wpłacającego
+        assert!(deposit_shares(LOCKED_VAULT_SHARES, 0, 0).is_err());
+    }
+
+    #[test]
+    fn deposit_minting_no_shares_is_rejected() {
+        assert!(deposit_shares(0, 1_000, 1_000).is_err());
+        assert!(deposit_shares(1, 1_000, 2_000).is_err());
+    }
+
+    #[test]
+    fn donation_does_not_steal_later_deposits() {
+        // Atakujący: minimalny pierwszy depozyt i przelew 1 LST prosto na skarbiec
+        let (attacker, locked) = deposit_shares(LOCKED_VAULT_SHARES + 1, 0, 0).unwrap();
+        let total_shares = attacker + locked;
+        let vault_balance = LOCKED_VAULT_SHARES + 1 + 1_000_000_000;
+
+        // Ofiara wpłaca 1 LST - traci mniej niż 0.1%
+        let deposit = 1_000_000_000;
+        let (victim, _) = deposit_shares(deposit, total_shares, vault_balance).unwrap();
+        let total_shares = total_shares + victim;
+        let vault_balance = vault_balance + deposit;
+        let victim_value = shares_to_msol(victim, total_shares, vault_balance).unwrap();
+        assert!(victim_value >= deposit - deposit / 1_000);
+
+        // Atakujący traci większość przelanego LST na rzecz zablokowanych udziałów
+        let attacker_value = shares_to_msol(attacker, total_shares, vault_balance).unwrap();
+        assert!(attacker_value < 1_000_000);
+    }
+
+    #[test]
+    fn shares_to_msol_rounds_down() {
+        assert_eq!(shares_to_msol(0, 0, 0).unwrap(), 0);
+        assert_eq!(shares_to_msol(1, 3, 10).unwrap(), 3);
+        assert_eq!(shares_to_msol(3, 3, 10).unwrap(), 10);
+        assert_eq!(shares_to_msol(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn burn_shares_round_up() {
+        // 1 token przy 3 udziałach na 10 tokenów: 0.3 udziału -> 1
+        assert_eq!(msol_to_shares_ceil(1, 3, 10).unwrap(), 1);
+        assert_eq!(msol_to_shares_ceil(10, 3, 10).unwrap(), 3);
+        assert_eq!(msol_to_shares_ceil(0, 3, 10).unwrap(), 0);
+        assert!(msol_to_shares_ceil(1, 3, 0).is_err());
+    }
+
+    #[test]
+    fn round_trip_never_favours_user() {
+        let (total_shares, vault_balance) = (999_983u64, 1_234_567u64);
+        for amount in [1u64, 2, 3, 17, 1_000, 99_999, 1_234_567] {
+            // Wpłata: udziały wyceniane z powrotem nie przekraczają wpłaty
+            let shares = shares_for_deposit(amount, total_shares, vault_balance).unwrap();
+            let value = shares_to_msol(shares, total_shares + shares, vault_balance + amount).unwrap();
+            assert!(value <= amount);
+
+            // Wypłata: spalone udziały pokrywają co najmniej wypłacone tokeny
+            let burned = msol_to_shares_ceil(amount, total_shares, vault_balance).unwrap();
+            assert!(shares_to_msol(burned, total_shares, vault_balance).unwrap() >= amount);
+        }
+    }
+
+    #[test]
+    fn weighted_base_value_rounds_up() {
+        // Pusta pozycja - kurs bieżący
+        assert_eq!(weighted_base_value(0, 0, 0, RATE).unwrap(), RATE);
+        assert_eq!(weighted_base_value(0, 1_000_000_000, 100, RATE).unwrap(), RATE);
+        // Równe ilości - średnia arytmetyczna
+        assert_eq!(
+            weighted_base_value(100, 1_000_000_000, 100, 1_200_000_000).unwrap(),
+            1_100_000_000
+        );
+        // (1 * 10 + 2 * 11) / 3 = 10.67 -> 11
+        assert_eq!(weighted_base_value(1, 10, 2, 11).unwrap(), 11);
+    }
+
+    #[test]
+    fn weighted_base_value_preserves_pending_dividend() {
+        let (existing, base, added) = (1_000_000_000u64, 1_000_000_000u64, 3_000_000_000u64);
+        let before = calculate_dividend(existing, base, RATE).unwrap();
+        let new_base = weighted_base_value(existing, base, added, RATE).unwrap();
+        let after = calculate_dividend(existing + added, new_base, RATE).unwrap();
+        assert!(after <= before);
+        assert!(before - after <= (existing + added) / 1_000_000_000 + 1);
+    }
+
+    #[test]
+    fn dividend_to_msol_rounds_down() {
+        assert_eq!(dividend_to_msol(1_100_000_000, RATE).unwrap(), 1_000_000_000);
+        assert_eq!(dividend_to_msol(1, RATE).unwrap(), 0);
+        assert_eq!(dividend_to_msol(2, RATE).unwrap(), 1);
+        assert!(dividend_to_msol(1, 0).is_err());
+    }
+
+    #[test]
+    fn calculate_bps_rounds_down() {
+        assert_eq!(calculate_bps(10_000, 1).unwrap(), 1);
+        assert_eq!(calculate_bps(9_999, 1).unwrap(), 0);
+        assert_eq!(calculate_bps(1_000, 0).unwrap(), 0);
+        assert_eq!(calculate_bps(1_000, 2_500).unwrap(), 250);
+        assert_eq!(calculate_bps(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn keeper_bounty_split_covers_whole_payout() {
+        for amount in [0u64, 1, 9, 10_001, 123_456_789, u64::MAX] {
+            for bps in [0u16, 1, 250, MAX_KEEPER_BOUNTY_BPS] {
+                let (bounty, owner_amount) = split_keeper_bounty(amount, bps).unwrap();
+                assert_eq!(bounty + owner_amount, amount);
+                assert_eq!(bounty, calculate_bps(amount, bps).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn keeper_bounty_capped_at_ten_percent() {
+        assert_eq!(MAX_KEEPER_BOUNTY_BPS, 1_000);
+        assert_eq!(split_keeper_bounty(1_000_000, MAX_KEEPER_BOUNTY_BPS).unwrap(), (100_000, 900_000));
+        // Reszta z zaokrąglenia zostaje u właściciela
+        assert_eq!(split_keeper_bounty(19, MAX_KEEPER_BOUNTY_BPS).unwrap(), (1, 18));
+    }
+
+    #[test]
+    fn split_by_bps_gives_dust_to_first_part() {
+        // 100 * 3333 / 10_000 = 33 dla każdej części, reszta 1 do pierwszej
+        assert_eq!(split_by_bps(100, &[3_334, 3_333, 3_333]).unwrap(), vec![34, 33, 33]);
+        assert_eq!(split_by_bps(1, &[5_000, 5_000]).unwrap(), vec![1, 0]);
+        assert_eq!(split_by_bps(0, &[5_000, 5_000]).unwrap(), vec![0, 0]);
+    }
+
+    #[test]
+    fn split_by_bps_single_recipient_takes_all() {
+        for amount in [0u64, 1, 9_999, u64::MAX] {
+            assert_eq!(split_by_bps(amount, &[10_000]).unwrap(), vec![amount]);
+        }
+    }
+
+    #[test]
+    fn split_by_bps_full_shares_cover_amount() {
+        for amount in [1u64, 7, 10_001, 123_456_789, u64::MAX] {
+            for shares in [&[10_000u16][..], &[1, 9_999], &[2_500, 2_500, 2_500, 2_500], &[1, 1, 9_998]] {
+                let parts = split_by_bps(amount, shares).unwrap();
+                assert_eq!(parts.len(), shares.len());
+                assert_eq!(parts.iter().map(|part| *part as u128).sum::<u128>(), amount as u128);
+                // Poza pierwszą częścią każda część to zaokrąglony w dół udział
+                for (part, bps) in parts.iter().zip(shares).skip(1) {
+                    assert_eq!(*part, calculate_bps(amount, *bps).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compound_interest_zero_rate_or_periods() {
+        assert_eq!(calculate_compound_interest(1_000_000, 0, 12).unwrap(), 0);
+        assert_eq!(calculate_compound_interest(1_000_000, 100, 0).unwrap(), 0);
+        assert_eq!(calculate_compound_interest(0, 100, 12).unwrap(), 0);
+    }
+
+    #[test]
+    fn compound_interest_compounds_each_period() {
+        assert_eq!(calculate_compound_interest(1_000_000, 100, 1).unwrap(), 10_000);
+        // 10_000 + 1% od 1_010_000
+        assert_eq!(calculate_compound_interest(1_000_000, 100, 2).unwrap(), 20_100);
+        // 100% za okres podwaja kapitał
+        assert_eq!(calculate_compound_interest(1_000, 10_000, 3).unwrap(), 7_000);
+    }
+
+    #[test]
+    fn compound_interest_rounds_down() {
+        assert_eq!(calculate_compound_interest(99, 100, 1).unwrap(), 0);
+        assert_eq!(calculate_compound_interest(150, 100, 2).unwrap(), 2);
+    }
+
+    #[test]
+    fn compound_interest_overflow_is_an_error() {
+        assert_eq!(calculate_compound_interest(u64::MAX, 10_000, 1).unwrap(), u64::MAX);
+        assert!(calculate_compound_interest(u64::MAX, 10_000, 2).is_err());
+    }
+
+    #[test]
+    fn management_fee_zero_inputs() {
+        assert_eq!(management_fee(0, 200, SECONDS_PER_YEAR).unwrap(), 0);
+        assert_eq!(management_fee(1_000_000, 0, SECONDS_PER_YEAR).unwrap(), 0);
+        assert_eq!(management_fee(1_000_000, 200, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn management_fee_is_linear_in_time() {
+        // Pełny rok = stawka roczna, pół roku = połowa
+        assert_eq!(management_fee(1_000_000, 200, SECONDS_PER_YEAR).unwrap(), 20_000);
+        assert_eq!(management_fee(1_000_000, 200, SECONDS_PER_YEAR / 2).unwrap(), 10_000);
+    }
+
+    #[test]
+    fn management_fee_rounds_down() {
+        // 1000 SOL przy 2% rocznie: 634.19 lamporta za sekundę -> 634
+        assert_eq!(management_fee(1_000_000_000_000, 200, 1).unwrap(), 634);
+        // Mała pozycja - opłata za sekundę zaokrąglona do zera
+        assert_eq!(management_fee(1_000_000_000, 200, 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn management_fee_overflow_is_an_error() {
+        assert!(management_fee(u64::MAX, 10_000, 2 * SECONDS_PER_YEAR).is_err());
+    }
+}
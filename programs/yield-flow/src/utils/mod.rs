@@ -4,52 +4,76 @@
 // - Harmonogramów wypłat (schedule)
 // - Integracji z Marinade Finance (marinade) 
 // - Obliczeń matematycznych (math)
+// - Swapów przez Jupiter (jupiter)
+// - Cen z wyroczni Pyth (oracle)
+// - Kursów LST z rejestru aktywów (lst, stake_pool)
 //
 // Główne komponenty:
 //
 // 1. Podmoduły funkcjonalne:
 //    * schedule.rs - zarządzanie terminami wypłat
 //    * marinade.rs - integracja z Marinade Finance:
-//      - Pobieranie kursu mSOL/SOL
+//      - Kurs mSOL -> SOL ze State (lamports_per_msol)
 //      - Operacje na stake'ach
+//    * stake_pool.rs - kurs tokenu puli SPL Stake Pool (jitoSOL, bSOL)
+//    * lst.rs - zweryfikowany odczyt kursu aktywa z rejestru (read_rate)
+//    * jupiter.rs - swap przez Jupiter z weryfikacją sald
+//    * oracle.rs - ceny Pyth i minimalna ilość wyjściowa swapu
 //    * math.rs - obliczenia finansowe:
 //      - Dywidendy
 //      - Odsetki składane
+//      - Księgowanie udziałów w skarbcu
 //
 // 2. Stałe matematyczne:
 //    * LAMPORTS_PER_SOL - 1_000_000_000 lamportów = 1 SOL
 //    * BPS_PER_PERCENT - 100 punktów bazowych = 1%
 //    * MAX_BPS - 10_000 = 100% (maksymalna wartość)
+//    * MAX_KEEPER_BOUNTY_BPS - 1_000 = 10% (limit nagrody keepera)
+//    * MAX_BENEFICIARIES - 5 (limit beneficjentów wypłaty pozycji)
+//    * MAX_PROJECTION_PERIODS - 120 (limit okresów projekcji kapitalizacji)
+//    * MAX_FEE_TIERS - 8 (limit progów opłaty protokołu)
+//    * MAX_MANAGEMENT_FEE_BPS - 500 = 5% rocznie (limit opłaty za zarządzanie)
+//    * MAX_PROTOCOL_FEE_BPS - 2_000 = 20% dywidendy (limit fee_rate i progów FeeTiers)
+//    * MAX_ORACLE_SLIPPAGE_BPS - 500 = 5% (limit tolerancji swapów keepera)
+//    * SECONDS_PER_YEAR - 31_536_000 (365 dni, naliczanie opłaty rocznej)
+//    * CONFIG_TIMELOCK_SECS - 172_800 (48h opóźnienia zmian krytycznych adresów)
+//    * LOCKED_VAULT_SHARES - 1_000 (udziały blokowane przy pierwszym depozycie skarbca)
 //
-// 3. Typy danych:
-//    * MsolRate - przechowuje kurs wymiany mSOL:
-//      - lamports_per_msol: wartość w lamportach SOL
-//      - last_updated: timestamp aktualizacji
-//
-// 4. Reeksportowane funkcje (dostępne bezpośrednio z utils):
-//    * get_msol_rate()
-//    * withdraw_stake_rewards() 
+// 3. Reeksportowane funkcje (dostępne bezpośrednio z utils):
+//    * read_rate() / lamports_per_msol()
 //    * calculate_dividend()
 //    * calculate_compound_interest()
+//    * shares_for_deposit() / deposit_shares() / shares_to_msol() / msol_to_shares_ceil()
+//    * calculate_bps() / split_keeper_bounty() / split_by_bps()
 
-use anchor_lang::AnchorDeserialize;
 pub mod schedule;
 pub mod marinade;
+pub mod jupiter;
+pub mod oracle;
 pub mod math;
+pub mod stake_pool;
+pub mod lst;
 
 
 // Re-eksport najczęściej używanych funkcji
-use crate::utils::marinade::{
-    get_msol_rate,
-    withdraw_stake_rewards,
-    WithdrawRewards,
-};
+pub use marinade::lamports_per_msol;
+pub use lst::read_rate;
 
 
 
 pub use math::{
     calculate_dividend,
     calculate_compound_interest,
+    shares_for_deposit,
+    deposit_shares,
+    shares_to_msol,
+    msol_to_shares_ceil,
+    weighted_base_value,
+    dividend_to_msol,
+    calculate_bps,
+    split_keeper_bounty,
+    split_by_bps,
+    management_fee,
 };
 
 /// Wspólne stałe matematyczne
@@ -62,18 +86,34 @@ pub mod constants {
     
     /// Maksymalna precyzja (100% = 10_000 punktów bazowych)
     pub const MAX_BPS: u16 = 10_000;
-}
 
-/// Wspólne typy dla modułów utils
-pub mod types {
-    use anchor_lang::prelude::*;
-    
-    /// Reprezentacja kursu mSOL/SOL
-    #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-    pub struct MsolRate {
-        /// Wartość 1 mSOL w lamportach SOL
-        pub lamports_per_msol: u64,
-        /// Czas ostatniej aktualizacji (unix timestamp)
-        pub last_updated: i64,
-    }
-}
\ No newline at end of file
+    /// Górny limit nagrody keepera (10% dywidendy)
+    pub const MAX_KEEPER_BOUNTY_BPS: u16 = 1_000;
+
+    /// Maksymalna liczba beneficjentów wypłaty jednej pozycji
+    pub const MAX_BENEFICIARIES: usize = 5;
+
+    /// Maksymalna liczba okresów w projekcji kapitalizacji (limit danych zwrotnych)
+    pub const MAX_PROJECTION_PERIODS: u64 = 120;
+
+    /// Maksymalna liczba progów w tabeli opłat protokołu
+    pub const MAX_FEE_TIERS: usize = 8;
+
+    /// Górny limit rocznej opłaty za zarządzanie (5% kapitału rocznie)
+    pub const MAX_MANAGEMENT_FEE_BPS: u16 = 500;
+
+    /// Górny limit opłaty protokołu od dywidendy (fee_rate i progi FeeTiers, 20%)
+    pub const MAX_PROTOCOL_FEE_BPS: u16 = 2_000;
+
+    /// Górny limit tolerancji slippage swapów keepera względem ceny z wyroczni (5%)
+    pub const MAX_ORACLE_SLIPPAGE_BPS: u16 = 500;
+
+    /// Liczba sekund w roku (365 dni) - podstawa naliczania opłaty rocznej
+    pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+    /// Opóźnienie wykonania zmian krytycznych adresów konfiguracji (48 godzin)
+    pub const CONFIG_TIMELOCK_SECS: i64 = 172_800;
+
+    /// Udziały bez właściciela blokowane przy pierwszym depozycie do skarbca
+    pub const LOCKED_VAULT_SHARES: u64 = 1_000;
+}
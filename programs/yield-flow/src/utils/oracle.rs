@@ -0,0 +1,158 @@
+// Moduł wyroczni cenowych Pyth
+//
+// Główne funkcjonalności:
+// 1. read_price() - odczytuje cenę z konta Pyth
+//    - Konto musi należeć do programu Pyth (InvalidOracleAccount)
+//    - Odrzuca ceny starsze niż max_age_secs (StaleOraclePrice)
+//    - Odrzuca ceny z przedziałem ufności > max_conf_bps ceny
+//      (OracleConfidenceTooWide)
+//    - Odrzuca ceny niedodatnie (InvalidOraclePrice)
+//
+// 2. min_out_from_prices() - oblicza minimalną ilość tokenów wyjściowych
+//    - Wartość wejścia przeliczana przez iloczyn cen (np. SOL/USD * mSOL/SOL)
+//    - Uwzględnia decimale mintów wejścia i wyjścia
+//    - Pomniejsza wynik o slippage_bps użytkownika
+//
+// Struktury:
+// - OraclePrice: cena (mantysa) i wykładnik dziesiętny
+//
+// Uwagi:
+// - Obliczenia w u128 z kontrolą overflow
+// - Cena "fair" z wyroczni jest podłogą dla trasy Jupitera - trasa
+//   zmanipulowana (sandwich) kończy się błędem SlippageExceeded
+
+use anchor_lang::{prelude::*, solana_program::pubkey};
+use pyth_sdk_solana::load_price_feed_from_account_info;
+use crate::{errors::ErrorCode, utils::constants::MAX_BPS};
+
+/// Adres programu wyroczni Pyth (właściciel kont cenowych)
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Cena z wyroczni: price * 10^expo
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: u64,
+    pub expo: i32,
+}
+
+/// Odczytuje i weryfikuje cenę z konta Pyth
+pub fn read_price(
+    price_account: &AccountInfo,
+    current_timestamp: i64,
+    max_age_secs: u64,
+    max_conf_bps: u16
+) -> Result<OraclePrice> {
+    // Dane konta weryfikowane tylko formatem - bez sprawdzenia właściciela
+    // każdy mógłby podstawić własne konto z dowolną ceną
+    require_keys_eq!(*price_account.owner, PYTH_PROGRAM_ID, ErrorCode::InvalidOracleAccount);
+
+    let feed = load_price_feed_from_account_info(price_account)
+        .map_err(|_| ErrorCode::InvalidOracleAccount)?;
+    let price = feed
+        .get_price_no_older_than(current_timestamp, max_age_secs)
+        .ok_or(ErrorCode::StaleOraclePrice)?;
+
+    require!(price.price > 0, ErrorCode::InvalidOraclePrice);
+    let value = price.price as u64;
+
+    // conf / price <= max_conf_bps / MAX_BPS
+    let conf_scaled = (price.conf as u128)
+        .checked_mul(MAX_BPS as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        conf_scaled <= (value as u128) * max_conf_bps as u128,
+        ErrorCode::OracleConfidenceTooWide
+    );
+
+    Ok(OraclePrice { price: value, expo: price.expo })
+}
+
+/// Minimalna ilość tokenów wyjściowych za `amount_in` po cenach z wyroczni
+pub fn min_out_from_prices(
+    amount_in: u64,
+    in_decimals: u8,
+    out_decimals: u8,
+    prices: &[OraclePrice],
+    slippage_bps: u16
+) -> Result<u64> {
+    require!(slippage_bps <= MAX_BPS, ErrorCode::InvalidSlippage);
+
+    let mut value = amount_in as u128;
+    let mut expo = out_decimals as i32 - in_decimals as i32;
+    for price in prices {
+        value = value
+            .checked_mul(price.price as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        expo += price.expo;
+    }
+
+    let scale = 10u128
+        .checked_pow(expo.unsigned_abs())
+        .ok_or(ErrorCode::MathOverflow)?;
+    let expected = if expo >= 0 {
+        value.checked_mul(scale).ok_or(ErrorCode::MathOverflow)?
+    } else {
+        value / scale
+    };
+
+    let min_out = expected
+        .checked_mul((MAX_BPS - slippage_bps) as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / MAX_BPS as u128;
+
+    u64::try_from(min_out).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL_USD: OraclePrice = OraclePrice { price: 15_000_000_000, expo: -8 }; // 150 USD
+    const MSOL_SOL: OraclePrice = OraclePrice { price: 110_000_000, expo: -8 }; // 1.1 SOL
+
+    #[test]
+    fn price_chain_with_negative_exponents() {
+        // 1 mSOL (9 decimali) -> USDC (6 decimali) = 1.1 * 150 = 165 USDC
+        assert_eq!(min_out_from_prices(1_000_000_000, 9, 6, &[SOL_USD, MSOL_SOL], 0).unwrap(), 165_000_000);
+        // Bez cen - tylko przeliczenie decimali
+        assert_eq!(min_out_from_prices(1_000_000_000, 9, 6, &[], 0).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn positive_and_zero_exponents() {
+        let hundreds = OraclePrice { price: 3, expo: 2 };
+        assert_eq!(min_out_from_prices(5, 6, 6, &[hundreds], 0).unwrap(), 1_500);
+        let unit = OraclePrice { price: 7, expo: 0 };
+        assert_eq!(min_out_from_prices(5, 6, 6, &[unit], 0).unwrap(), 35);
+        // Wykładniki sumują się z różnicą decimali
+        assert_eq!(min_out_from_prices(5, 8, 6, &[hundreds], 0).unwrap(), 15);
+    }
+
+    #[test]
+    fn mixed_decimals() {
+        let one = OraclePrice { price: 1, expo: 0 };
+        // Więcej decimali na wyjściu - mnożenie
+        assert_eq!(min_out_from_prices(1_000_000, 6, 9, &[one], 0).unwrap(), 1_000_000_000);
+        // Mniej decimali na wyjściu - ucięcie w dół
+        assert_eq!(min_out_from_prices(1_999, 9, 6, &[one], 0).unwrap(), 1);
+        assert_eq!(min_out_from_prices(999, 9, 6, &[one], 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn slippage_rounds_down() {
+        let one = OraclePrice { price: 1, expo: 0 };
+        // 999 * 9_950 / 10_000 = 994.005
+        assert_eq!(min_out_from_prices(999, 6, 6, &[one], 50).unwrap(), 994);
+        assert_eq!(min_out_from_prices(1, 6, 6, &[one], 1).unwrap(), 0);
+        assert_eq!(min_out_from_prices(999, 6, 6, &[one], MAX_BPS).unwrap(), 0);
+        assert!(min_out_from_prices(999, 6, 6, &[one], MAX_BPS + 1).is_err());
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let huge = OraclePrice { price: u64::MAX, expo: 0 };
+        assert!(min_out_from_prices(u64::MAX, 6, 6, &[huge, huge], 0).is_err());
+        let big_expo = OraclePrice { price: 1, expo: 40 };
+        assert!(min_out_from_prices(1, 6, 6, &[big_expo], 0).is_err());
+    }
+}
@@ -10,8 +10,11 @@
 //    Obsługiwane harmonogramy:
 //    - Disabled: zwraca 0 (brak wypłat)
 //    - Daily: dodaje 86400 sekund (24h)
-//    - Weekly: oblicza dzień tygodnia i dolicza odpowiednią liczbę dni
-//    - Monthly: uproszczona implementacja (30 dni) + wybrany dzień miesiąca
+//    - Weekly: najbliższy wybrany dzień tygodnia o wybranej godzinie lokalnej
+//    - Monthly: wybrany dzień (1-28) następnego miesiąca kalendarzowego
+//      o wybranej godzinie lokalnej
+//    - Weekly/Monthly liczone w czasie lokalnym (PayoutTime: godzina +
+//      stałe przesunięcie UTC), wynik zawsze zwracany w UTC
 //    - Custom: dodaje podany interwał w sekundach
 //
 // 2. should_payout() - decyduje czy wypłata powinna być wykonana
@@ -25,22 +28,38 @@
 //    Sprawdza:
 //    - Dni tygodnia (0-6 dla Weekly)
 //    - Dni miesiąca (1-28 dla Monthly)
+//    - Godzinę 0-23 i przesunięcie UTC -12:00..+14:00 (Weekly/Monthly)
 //    - Dodatni interwał dla Custom
 //
 // Obsługa błędów:
 // - InvalidWeekday - niepoprawny dzień tygodnia
 // - InvalidMonthDay - niepoprawny dzień miesiąca  
 // - InvalidCustomInterval - niepoprawny interwał
+// - InvalidHourOfDay - niepoprawna godzina wypłaty
+// - InvalidUtcOffset - przesunięcie UTC poza zakresem
+//
+// 4. Kalendarz cywilny (gregoriański, proleptyczny):
+//    - days_from_civil() - data (rok, miesiąc, dzień) -> dni od 1970-01-01
+//    - civil_from_days() - dni od 1970-01-01 -> data (rok, miesiąc, dzień)
+//    - Algorytm "days from civil" (H. Hinnant), bez zależności od std::time
+//    - Obsługuje lata przestępne i przejście przez koniec roku
 //
 // Uwagi:
-// - Implementacja Monthly jest uproszczona (stałe 30 dni)
 // - Obliczenia oparte na timestampach UNIX (sekundy od 1970)
 // - Wszystkie funkcje są metodami statycznymi struktury ScheduleCalculator
 
 
 use anchor_lang::prelude::*;
-use crate::state::{UserStake, PayoutSchedule};
+use crate::state::{UserStake, PayoutSchedule, PayoutTime};
 use crate::errors::ErrorCode;
+
+const SECONDS_PER_DAY: i64 = 86400;
+const SECONDS_PER_HOUR: i64 = 3600;
+
+/// Dopuszczalny zakres przesunięcia UTC w minutach (UTC-12:00 .. UTC+14:00)
+const MIN_UTC_OFFSET_MINUTES: i16 = -720;
+const MAX_UTC_OFFSET_MINUTES: i16 = 840;
+
 pub struct ScheduleCalculator;
 
 impl ScheduleCalculator {
@@ -53,26 +72,41 @@ impl ScheduleCalculator {
             PayoutSchedule::Disabled => Ok(0),
             PayoutSchedule::Daily => Ok(current_timestamp + 86400), // 24 godziny
             
-            PayoutSchedule::Weekly(weekday) => {
-                // Oblicz dzień tygodnia (0 = Niedziela, 6 = Sobota)
-                let days_since_epoch = current_timestamp / 86400;
-                let current_weekday = (days_since_epoch + 4) % 7; // Dostosowanie do timestampów
-                
-                // Oblicz dni do następnego wybranego dnia tygodnia
-                let days_to_add = if weekday as i64 > current_weekday {
-                    weekday as i64 - current_weekday
-                } else {
-                    7 - (current_weekday - weekday as i64)
-                };
-                
-                Ok(current_timestamp + days_to_add * 86400)
+            PayoutSchedule::Weekly(weekday, time) => {
+                Self::validate_schedule(&schedule)?;
+
+                // Obliczenia w lokalnym czasie użytkownika
+                let local_now = Self::to_local(current_timestamp, time);
+                let today = local_now.div_euclid(SECONDS_PER_DAY);
+                let current_weekday = (today + 4).rem_euclid(7); // 1970-01-01 to czwartek (0 = Niedziela)
+
+                // Najbliższy wybrany dzień tygodnia o wybranej godzinie (ściśle po teraz)
+                let days_to_add = (weekday as i64 - current_weekday).rem_euclid(7);
+                let mut local_payout = (today + days_to_add) * SECONDS_PER_DAY
+                    + time.hour as i64 * SECONDS_PER_HOUR;
+                if local_payout <= local_now {
+                    local_payout += 7 * SECONDS_PER_DAY;
+                }
+
+                Ok(Self::to_utc(local_payout, time))
             },
             
-            PayoutSchedule::Monthly(day_of_month) => {
-                // Uproszczona implementacja - zawsze 30 dni
-                let next_month_timestamp = current_timestamp + 30 * 86400;
-                // Ustawiamy na wybrany dzień miesiąca (1-28)
-                Ok(next_month_timestamp - (next_month_timestamp % 86400) + ((day_of_month as i64 - 1) * 86400))
+            PayoutSchedule::Monthly(day_of_month, time) => {
+                Self::validate_schedule(&schedule)?;
+
+                // Wybrany dzień następnego miesiąca kalendarzowego (czas lokalny)
+                let local_now = Self::to_local(current_timestamp, time);
+                let today = local_now.div_euclid(SECONDS_PER_DAY);
+                let (year, month, _) = Self::civil_from_days(today);
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+
+                let payout_day = Self::days_from_civil(next_year, next_month, day_of_month as u32);
+                let local_payout = payout_day * SECONDS_PER_DAY + time.hour as i64 * SECONDS_PER_HOUR;
+                Ok(Self::to_utc(local_payout, time))
             },
             
             PayoutSchedule::Custom(interval_secs) => {
@@ -103,16 +137,252 @@ impl ScheduleCalculator {
         schedule: &PayoutSchedule
     ) -> Result<()> {
         match schedule {
-            PayoutSchedule::Weekly(day) if *day > 6 => {
+            PayoutSchedule::Weekly(day, _) if *day > 6 => {
                 Err(ErrorCode::InvalidWeekday.into())
             },
-            PayoutSchedule::Monthly(day) if *day < 1 || *day > 28 => {
+            PayoutSchedule::Monthly(day, _) if *day < 1 || *day > 28 => {
                 Err(ErrorCode::InvalidMonthDay.into())
             },
+            PayoutSchedule::Weekly(_, time) | PayoutSchedule::Monthly(_, time) => {
+                Self::validate_time(time)
+            },
             PayoutSchedule::Custom(secs) if *secs <= 0 => {
                 Err(ErrorCode::InvalidCustomInterval.into())
             },
             _ => Ok(())
         }
     }
+
+    /// Weryfikuje lokalną godzinę wypłaty i przesunięcie UTC
+    fn validate_time(time: &PayoutTime) -> Result<()> {
+        require!(time.hour <= 23, ErrorCode::InvalidHourOfDay);
+        require!(
+            (MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&time.utc_offset_minutes),
+            ErrorCode::InvalidUtcOffset
+        );
+        Ok(())
+    }
+
+    /// Timestamp UTC -> "timestamp" w lokalnym czasie użytkownika
+    fn to_local(utc_timestamp: i64, time: PayoutTime) -> i64 {
+        utc_timestamp + time.utc_offset_minutes as i64 * 60
+    }
+
+    /// Lokalny "timestamp" -> timestamp UTC (zapisywany w next_payout_date)
+    fn to_utc(local_timestamp: i64, time: PayoutTime) -> i64 {
+        local_timestamp - time.utc_offset_minutes as i64 * 60
+    }
+
+    /// Liczba dni od 1970-01-01 dla daty w kalendarzu gregoriańskim
+    pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+        // Rok liczony od marca - luty jest ostatnim miesiącem roku
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400; // [0, 399]
+        let mp = (month as i64 + 9) % 12; // marzec = 0
+        let doy = (153 * mp + 2) / 5 + day as i64 - 1; // [0, 365]
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Data (rok, miesiąc 1-12, dzień 1-31) dla liczby dni od 1970-01-01
+    pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097; // [0, 146096]
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365; // [0, 399]
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+        let mp = (5 * doy + 2) / 153; // [0, 11]
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_leap(year: i64) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    fn days_in_month(year: i64, month: u32) -> u32 {
+        match month {
+            2 if is_leap(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    #[test]
+    fn civil_conversion_matches_naive_calendar() {
+        // Liczymy dzień po dniu od 1970-01-01 do końca 2100 roku
+        let (mut year, mut month, mut day) = (1970i64, 1u32, 1u32);
+        for days in 0..47_847i64 {
+            assert_eq!(ScheduleCalculator::days_from_civil(year, month, day), days);
+            assert_eq!(ScheduleCalculator::civil_from_days(days), (year, month, day));
+
+            day += 1;
+            if day > days_in_month(year, month) {
+                day = 1;
+                month += 1;
+                if month > 12 {
+                    month = 1;
+                    year += 1;
+                }
+            }
+        }
+        assert_eq!((year, month, day), (2101, 1, 1));
+    }
+
+    #[test]
+    fn known_dates() {
+        assert_eq!(ScheduleCalculator::days_from_civil(1970, 1, 1), 0);
+        assert_eq!(ScheduleCalculator::days_from_civil(2000, 3, 1), 11_017);
+        assert_eq!(ScheduleCalculator::days_from_civil(2024, 2, 29), 19_782);
+        assert_eq!(ScheduleCalculator::civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn monthly_lands_on_chosen_day_of_next_month() {
+        // Każdy dzień 2023-2030 (w tym 2024 i 2028 przestępne), każdy dzień wypłaty
+        let start = ScheduleCalculator::days_from_civil(2023, 1, 1);
+        let end = ScheduleCalculator::days_from_civil(2031, 1, 1);
+
+        for today in start..end {
+            let (year, month, _) = ScheduleCalculator::civil_from_days(today);
+            let (expected_year, expected_month) = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+
+            for payout_day in 1..=28u8 {
+                // Początek, środek i ostatnia sekunda dnia
+                for offset in [0, 43_200, SECONDS_PER_DAY - 1] {
+                    let now = today * SECONDS_PER_DAY + offset;
+                    let next = ScheduleCalculator::calculate_next_payout(
+                        PayoutSchedule::Monthly(payout_day, PayoutTime::default()),
+                        now
+                    ).unwrap();
+
+                    assert!(next > now);
+                    assert_eq!(next % SECONDS_PER_DAY, 0);
+                    assert_eq!(
+                        ScheduleCalculator::civil_from_days(next / SECONDS_PER_DAY),
+                        (expected_year, expected_month, payout_day as u32)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn monthly_year_rollover() {
+        let dec_31 = ScheduleCalculator::days_from_civil(2024, 12, 31) * SECONDS_PER_DAY;
+        let next = ScheduleCalculator::calculate_next_payout(PayoutSchedule::Monthly(15, PayoutTime::default()), dec_31).unwrap();
+        assert_eq!(next, ScheduleCalculator::days_from_civil(2025, 1, 15) * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn monthly_after_leap_day() {
+        let leap_day = ScheduleCalculator::days_from_civil(2028, 2, 29) * SECONDS_PER_DAY + 3600;
+        let next = ScheduleCalculator::calculate_next_payout(PayoutSchedule::Monthly(28, PayoutTime::default()), leap_day).unwrap();
+        assert_eq!(next, ScheduleCalculator::days_from_civil(2028, 3, 28) * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn monthly_rejects_invalid_day() {
+        assert!(ScheduleCalculator::calculate_next_payout(PayoutSchedule::Monthly(0, PayoutTime::default()), 0).is_err());
+        assert!(ScheduleCalculator::calculate_next_payout(PayoutSchedule::Monthly(29, PayoutTime::default()), 0).is_err());
+    }
+
+    fn local_time(hour: u8, utc_offset_minutes: i16) -> PayoutTime {
+        PayoutTime { hour, utc_offset_minutes }
+    }
+
+    #[test]
+    fn weekly_uses_local_hour_and_offset() {
+        // 2024-06-03 to poniedziałek; 06:00 UTC = 08:00 CEST
+        let monday = ScheduleCalculator::days_from_civil(2024, 6, 3) * SECONDS_PER_DAY;
+        let now = monday + 6 * SECONDS_PER_HOUR;
+
+        // Poniedziałek 09:00 CEST -> jeszcze dziś, 07:00 UTC
+        let next = ScheduleCalculator::calculate_next_payout(
+            PayoutSchedule::Weekly(1, local_time(9, 120)),
+            now
+        ).unwrap();
+        assert_eq!(next, monday + 7 * SECONDS_PER_HOUR);
+
+        // Poniedziałek 08:00 CEST -> właśnie minęło, za tydzień
+        let next = ScheduleCalculator::calculate_next_payout(
+            PayoutSchedule::Weekly(1, local_time(8, 120)),
+            now
+        ).unwrap();
+        assert_eq!(next, monday + 7 * SECONDS_PER_DAY + 6 * SECONDS_PER_HOUR);
+    }
+
+    #[test]
+    fn weekly_local_weekday_around_midnight() {
+        // Niedziela 23:30 UTC = poniedziałek 00:30 CET
+        let sunday = ScheduleCalculator::days_from_civil(2024, 1, 7) * SECONDS_PER_DAY;
+        let now = sunday + 23 * SECONDS_PER_HOUR + 1800;
+
+        // Wtorek 00:00 CET = poniedziałek 23:00 UTC
+        let next = ScheduleCalculator::calculate_next_payout(
+            PayoutSchedule::Weekly(2, local_time(0, 60)),
+            now
+        ).unwrap();
+        assert_eq!(next, sunday + SECONDS_PER_DAY + 23 * SECONDS_PER_HOUR);
+    }
+
+    #[test]
+    fn weekly_always_within_next_seven_days() {
+        let start = ScheduleCalculator::days_from_civil(2024, 1, 1) * SECONDS_PER_DAY;
+        for step in 0..(14 * 24 * 4) {
+            let now = start + step * 900;
+            for weekday in 0..=6u8 {
+                for offset in [-300i16, 0, 60, 120, 330] {
+                    let time = local_time(9, offset);
+                    let next = ScheduleCalculator::calculate_next_payout(
+                        PayoutSchedule::Weekly(weekday, time),
+                        now
+                    ).unwrap();
+                    assert!(next > now && next <= now + 7 * SECONDS_PER_DAY);
+
+                    let local = ScheduleCalculator::to_local(next, time);
+                    assert_eq!(local.rem_euclid(SECONDS_PER_DAY), 9 * SECONDS_PER_HOUR);
+                    assert_eq!((local.div_euclid(SECONDS_PER_DAY) + 4).rem_euclid(7), weekday as i64);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn monthly_in_local_frame() {
+        // 2024-01-31 23:30 UTC = 2024-02-01 01:30 EET (UTC+2, offset +120) -> następny miesiąc to marzec
+        let now = ScheduleCalculator::days_from_civil(2024, 1, 31) * SECONDS_PER_DAY
+            + 23 * SECONDS_PER_HOUR + 1800;
+        let next = ScheduleCalculator::calculate_next_payout(
+            PayoutSchedule::Monthly(1, local_time(0, 120)),
+            now
+        ).unwrap();
+
+        // 2024-03-01 00:00 EET = 2024-02-29 22:00 UTC
+        assert_eq!(
+            next,
+            ScheduleCalculator::days_from_civil(2024, 2, 29) * SECONDS_PER_DAY + 22 * SECONDS_PER_HOUR
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_local_time() {
+        assert!(ScheduleCalculator::validate_schedule(&PayoutSchedule::Weekly(1, local_time(24, 0))).is_err());
+        assert!(ScheduleCalculator::validate_schedule(&PayoutSchedule::Monthly(1, local_time(0, 841))).is_err());
+        assert!(ScheduleCalculator::validate_schedule(&PayoutSchedule::Monthly(1, local_time(0, -721))).is_err());
+        assert!(ScheduleCalculator::validate_schedule(&PayoutSchedule::Weekly(1, local_time(23, -720))).is_ok());
+    }
 }
\ No newline at end of file
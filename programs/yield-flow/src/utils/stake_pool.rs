@@ -0,0 +1,65 @@
+// Moduł odczytu kursu z puli SPL Stake Pool (jitoSOL, bSOL, ...)
+//
+// Główne funkcjonalności:
+// 1. lamports_per_pool_token() - kurs tokenu puli w lamportach SOL
+//    - kurs = total_lamports * 1e9 / pool_token_supply (zaokrąglenie w dół)
+//    - Weryfikuje typ konta (StakePool) i mint tokenu puli
+//    - Odrzuca pulę nieaktualizowaną w bieżącej epoce (StakePoolNotUpdated)
+//
+// Układ konta StakePool (borsh, spl-stake-pool):
+// - account_type: u8 (1 = StakePool)                      offset 0
+// - manager, staker, stake_deposit_authority: Pubkey      offset 1..97
+// - stake_withdraw_bump_seed: u8                          offset 97
+// - validator_list, reserve_stake: Pubkey                 offset 98..162
+// - pool_mint: Pubkey                                     offset 162
+// - manager_fee_account, token_program_id: Pubkey         offset 194..258
+// - total_lamports: u64                                   offset 258
+// - pool_token_supply: u64                                offset 266
+// - last_update_epoch: u64                                offset 274
+//
+// Uwagi:
+// - Odczyt bezpośrednio z danych konta (bez zależności od spl-stake-pool)
+// - Funkcja czysta - używana on-chain i przez keepera
+
+use anchor_lang::prelude::*;
+use crate::{errors::ErrorCode, utils::constants::LAMPORTS_PER_SOL};
+
+const ACCOUNT_TYPE_STAKE_POOL: u8 = 1;
+const POOL_MINT_OFFSET: usize = 162;
+const TOTAL_LAMPORTS_OFFSET: usize = 258;
+const POOL_TOKEN_SUPPLY_OFFSET: usize = 266;
+const LAST_UPDATE_EPOCH_OFFSET: usize = 274;
+
+/// Wartość 1 tokenu puli (1e9 jednostek) w lamportach
+pub fn lamports_per_pool_token(data: &[u8], pool_mint: &Pubkey, current_epoch: u64) -> Result<u64> {
+    require!(
+        data.len() >= LAST_UPDATE_EPOCH_OFFSET + 8 && data[0] == ACCOUNT_TYPE_STAKE_POOL,
+        ErrorCode::InvalidStakePool
+    );
+    require!(
+        data[POOL_MINT_OFFSET..POOL_MINT_OFFSET + 32] == pool_mint.to_bytes(),
+        ErrorCode::InvalidStakePool
+    );
+    require!(
+        read_u64(data, LAST_UPDATE_EPOCH_OFFSET) >= current_epoch,
+        ErrorCode::StakePoolNotUpdated
+    );
+
+    let total_lamports = read_u64(data, TOTAL_LAMPORTS_OFFSET);
+    let pool_token_supply = read_u64(data, POOL_TOKEN_SUPPLY_OFFSET);
+    require!(pool_token_supply > 0, ErrorCode::InvalidStakePool);
+
+    let rate = (total_lamports as u128)
+        .checked_mul(LAMPORTS_PER_SOL as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        / pool_token_supply as u128;
+    require!(rate > 0, ErrorCode::InvalidStakePool);
+
+    u64::try_from(rate).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
@@ -0,0 +1,199 @@
+// Przebieg keepera na solana-test-validator (anchor test)
+//
+// Walidator (Anchor.toml):
+// - localnet_mock pod adresem Jupitera v6 - trasa swapu po stałym kursie
+//   i pula w układzie SPL StakePool ze sterowanym kursem
+// - konta Pyth SOL/USD (150) i LST/SOL (1.1) z tests/fixtures
+//
+// Scenariusz:
+// 1. Konfiguracja programu, rejestr aktywa LST (pula mock), token wypłaty (USD)
+// 2. Pozycja użytkownika z auto-wypłatą co sekundę w tokenie wypłaty
+// 3. Wzrost kursu puli o 10% -> dywidenda do wypłaty
+// 4. Keeper (KEEPER_ROUTE_SOURCE=local, KEEPER_RUN_ONCE=true) wypłaca dywidendę
+//    przez batch_crank_claim
+
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import {
+  createAssociatedTokenAccountIdempotent,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { execFileSync } from "child_process";
+import { assert } from "chai";
+import { YieldFlow } from "../target/types/yield_flow";
+import { LocalnetMock } from "../target/types/localnet_mock";
+
+const MARINADE_PROGRAM = new PublicKey("MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD");
+const MSOL_MINT = new PublicKey("mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So");
+const SOL_USD_ORACLE = new PublicKey("4GnArmovtBqxLC6vs3ukfs2HK2dwcDkKifaGfroXnxxo");
+const LST_SOL_ORACLE = new PublicKey("3er4J9K4zd9BTkK7tf6yv2VGa1oGWY2QsdTRiWbLdZaE");
+
+const LST = new BN(LAMPORTS_PER_SOL);
+const POOL_SUPPLY = new BN(1_000_000).mul(LST);
+// Kurs trasy: 170 USD za 1 LST (podłoga z wyroczni: 150 * 1.1 minus slippage)
+const LOCAL_ROUTE_RATE = "170000000";
+
+describe("keeper", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.yieldFlow as Program<YieldFlow>;
+  const mock = anchor.workspace.localnetMock as Program<LocalnetMock>;
+  const admin = provider.wallet as anchor.Wallet;
+  const user = Keypair.generate();
+
+  const pda = (seeds: (Buffer | Uint8Array)[], programId = program.programId) =>
+    PublicKey.findProgramAddressSync(seeds, programId)[0];
+
+  let lstMint: PublicKey;
+  let payoutMint: PublicKey;
+  let stakePool: PublicKey;
+
+  before(async () => {
+    const airdrop = await provider.connection.requestAirdrop(user.publicKey, 10 * LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(airdrop);
+
+    await program.methods
+      .initializeProgram({ feesEnabled: false, feeRate: 0, managementFeeBps: 0 })
+      .accountsPartial({
+        admin: admin.publicKey,
+        marinadeProgram: MARINADE_PROGRAM,
+        msolMint: MSOL_MINT,
+      })
+      .rpc();
+    await program.methods.initializeRoles().accountsPartial({ admin: admin.publicKey }).rpc();
+    // Stały publish_time kont z fixtures - wiek ceny praktycznie bez limitu
+    await program.methods
+      .setOracles(new BN(10 * 365 * 24 * 3600), 100, 100)
+      .accountsPartial({
+        admin: admin.publicKey,
+        solUsdOracle: SOL_USD_ORACLE,
+        msolSolOracle: LST_SOL_ORACLE,
+      })
+      .rpc();
+
+    // Aktywo LST z pulą mock (kurs 1 SOL za token)
+    lstMint = await createMint(provider.connection, admin.payer, admin.publicKey, null, 9);
+    stakePool = pda([Buffer.from("stake-pool"), lstMint.toBuffer()], mock.programId);
+    await mock.methods
+      .createStakePool(POOL_SUPPLY, POOL_SUPPLY)
+      .accountsPartial({ payer: admin.publicKey, poolMint: lstMint })
+      .rpc();
+    await program.methods
+      .registerLstAsset({ splStakePool: {} }, mock.programId)
+      .accountsPartial({
+        admin: admin.publicKey,
+        mint: lstMint,
+        poolState: stakePool,
+        lstSolOracle: LST_SOL_ORACLE,
+      })
+      .rpc();
+    await program.methods
+      .initializeVault()
+      .accountsPartial({ admin: admin.publicKey, lstMint })
+      .rpc();
+
+    payoutMint = await createMint(provider.connection, admin.payer, admin.publicKey, null, 6);
+    await program.methods
+      .addPayoutToken({ usd: {} })
+      .accountsPartial({ registryManager: admin.publicKey, mint: payoutMint })
+      .rpc();
+
+    // Konta tokenu wypłaty: skarbiec (cel swapu), keeper, użytkownik
+    const vault = pda([Buffer.from("vault"), lstMint.toBuffer()]);
+    for (const [owner, offCurve] of [
+      [vault, true],
+      [admin.publicKey, false],
+      [user.publicKey, false],
+    ] as [PublicKey, boolean][]) {
+      await createAssociatedTokenAccountIdempotent(
+        provider.connection,
+        admin.payer,
+        payoutMint,
+        owner,
+        {},
+        undefined,
+        undefined,
+        offCurve
+      );
+    }
+
+    // Pula trasy mock: przyjmuje LST, wypłaca token wypłaty
+    const pool = pda([Buffer.from("pool")], mock.programId);
+    await createAssociatedTokenAccountIdempotent(
+      provider.connection, admin.payer, lstMint, pool, {}, undefined, undefined, true
+    );
+    const poolPayout = await createAssociatedTokenAccountIdempotent(
+      provider.connection, admin.payer, payoutMint, pool, {}, undefined, undefined, true
+    );
+    await mintTo(provider.connection, admin.payer, payoutMint, poolPayout, admin.payer, 1_000_000_000_000);
+  });
+
+  it("pays a due dividend through the local route", async () => {
+    const userLst = await createAssociatedTokenAccountIdempotent(
+      provider.connection, admin.payer, lstMint, user.publicKey
+    );
+    await mintTo(provider.connection, admin.payer, lstMint, userLst, admin.payer, BigInt(10 * LAMPORTS_PER_SOL));
+
+    const userStake = pda([Buffer.from("user-stake"), user.publicKey.toBuffer(), lstMint.toBuffer()]);
+    await program.methods
+      .initializeUserStake(new BN(10).mul(LST))
+      .accountsPartial({ user: user.publicKey, userLst, poolState: stakePool })
+      .signers([user])
+      .rpc();
+    await program.methods
+      .setPayoutToken()
+      .accountsPartial({
+        userStake,
+        user: user.publicKey,
+        payoutToken: pda([Buffer.from("payout-token"), payoutMint.toBuffer()]),
+      })
+      .signers([user])
+      .rpc();
+    await program.methods
+      .setSchedule({
+        schedule: { custom: { 0: new BN(1) } },
+        autoClaim: true,
+        minAmount: new BN(0),
+        payoutMode: { usdc: {} },
+        maxUnstakeFeeBps: 0,
+        autoCompound: false,
+        payoutBps: 10_000,
+      })
+      .accountsPartial({ userStake, user: user.publicKey })
+      .signers([user])
+      .rpc();
+
+    // +10% kursu puli
+    await mock.methods
+      .setStakePoolRate(POOL_SUPPLY.muln(11).divn(10), POOL_SUPPLY)
+      .accountsPartial({ poolMint: lstMint })
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 2_000));
+
+    execFileSync("cargo", ["run", "--quiet", "-p", "yieldflow-keeper"], {
+      stdio: "inherit",
+      env: {
+        ...process.env,
+        KEEPER_RPC_URL: provider.connection.rpcEndpoint,
+        KEEPER_KEYPAIR: process.env.ANCHOR_WALLET,
+        KEEPER_ROUTE_SOURCE: "local",
+        KEEPER_LOCAL_ROUTE_RATE: LOCAL_ROUTE_RATE,
+        KEEPER_RUN_ONCE: "true",
+      },
+    });
+
+    const userPayout = await getAccount(
+      provider.connection,
+      getAssociatedTokenAddressSync(payoutMint, user.publicKey)
+    );
+    assert.isTrue(userPayout.amount > BigInt(0), "dividend not paid to the owner");
+
+    const stake = await program.account.userStake.fetch(userStake);
+    assert.isTrue(stake.totalDividends.gtn(0), "dividend not recorded");
+  });
+});